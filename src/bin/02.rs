@@ -1,3 +1,4 @@
+use advent_of_code::utils::math;
 use anyhow::{Context, Result};
 
 advent_of_code::solution!(2);
@@ -20,24 +21,11 @@ fn has_odd_digits(n: u64) -> bool {
     (digits & 1) != 0
 }
 
-fn is_rep_digit(n: u64) -> bool {
-    let mut prev = None;
-    let mut num = n;
-    while num != 0 {
-        let digit = num % 10;
-        num /= 10;
-        if let Some(prev) = prev {
-            if digit != prev {
-                return false;
-            }
-        }
-        prev = Some(digit);
-    }
-    true
-}
-
+/// Proper divisors of `n`, i.e. every divisor but `n` itself.
 fn find_all_divisors(n: u64) -> Vec<u64> {
-    (1..n).filter(|&d| n % d == 0).collect()
+    let mut divisors = math::divisors(n);
+    divisors.pop();
+    divisors
 }
 
 fn are_all_patterns_equal(patterns: &[&str]) -> bool {
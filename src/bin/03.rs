@@ -22,7 +22,7 @@ pub fn part_one(input: &str) -> Option<u64> {
     Some(output_joltage)
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
+pub fn part_two(_input: &str) -> Option<u64> {
     None
 }
 
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use advent_of_code::utils::{Map2D, Pos2D};
+use advent_of_code::utils::Map2D;
 use anyhow::Result;
 
 advent_of_code::solution!(4);
@@ -14,60 +14,39 @@ enum TileType {
 impl Display for TileType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            TileType::PaperRoll => write!(f, "{}", "@"),
-            TileType::Empty => write!(f, "{}", "."),
+            TileType::PaperRoll => write!(f, "@"),
+            TileType::Empty => write!(f, "."),
         }
     }
 }
 
+impl TryFrom<char> for TileType {
+    type Error = char;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '@' => Ok(TileType::PaperRoll),
+            '.' => Ok(TileType::Empty),
+            _ => Err(value),
+        }
+    }
+}
+
+fn is_paper_roll(tile: &TileType) -> bool {
+    *tile == TileType::PaperRoll
+}
+
 #[derive(Debug)]
 struct Map {
     pub base: Map2D<TileType>,
 }
 
 fn from_input(input: &str) -> Result<Map> {
-    let height = input.lines().count();
-    let width = input.lines().last().unwrap().chars().count();
-
-    let mut tiles = vec![TileType::Empty; height * width];
-
-    for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            let position = Pos2D::new(x as i32, y as i32);
-
-            let tile = match c {
-                '@' => TileType::PaperRoll,
-                '.' => TileType::Empty,
-                _ => panic!("Unknown tile: {}", c),
-            };
-
-            let idx = position.to_idx(width, height).unwrap();
-            tiles[idx] = tile;
-        }
-    }
-
     Ok(Map {
-        base: Map2D {
-            width,
-            height,
-            tiles,
-            start: 0,
-            end: 0,
-        },
+        base: input.parse()?,
     })
 }
 
-fn get_ccessible_paper_rolls(map: &Map) -> Vec<usize> {
-    map.base
-        .tiles
-        .iter()
-        .enumerate()
-        .filter(|&(_idx, &tile)| tile == TileType::PaperRoll)
-        .filter(|&(idx, _tile)| map.base.get_neighbors(idx, TileType::PaperRoll, true).len() < 4)
-        .map(|(idx, _tile)| idx)
-        .collect()
-}
-
 pub fn part_one(input: &str) -> Option<u64> {
     let map = from_input(input).unwrap();
     let mut accessible_paper_rolls = 0;
@@ -77,8 +56,7 @@ pub fn part_one(input: &str) -> Option<u64> {
             continue;
         }
 
-        let neighbors = map.base.get_neighbors(idx, TileType::PaperRoll, true);
-        if neighbors.len() < 4 {
+        if map.base.count_neighbors(idx, true, is_paper_roll) < 4 {
             accessible_paper_rolls += 1;
         }
     }
@@ -87,17 +65,39 @@ pub fn part_one(input: &str) -> Option<u64> {
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    let mut map = from_input(input).unwrap();
-    let mut removed_rps = 0;
-    loop {
-        let accessible_prs = get_ccessible_paper_rolls(&map);
-        if accessible_prs.is_empty() {
-            break;
+    let map = from_input(input).unwrap().base;
+
+    // every paper roll starts with its number of paper roll neighbors, and the rolls that
+    // are already accessible make up the initial worklist
+    let mut neighbor_counts = vec![0; map.tiles.len()];
+    let mut removed = vec![false; map.tiles.len()];
+    let mut worklist = vec![];
+    for (idx, &tile) in map.tiles.iter().enumerate() {
+        if tile != TileType::PaperRoll {
+            continue;
         }
 
-        for pr_idx in accessible_prs {
-            map.base.tiles[pr_idx] = TileType::Empty;
-            removed_rps += 1;
+        neighbor_counts[idx] = map.count_neighbors(idx, true, is_paper_roll);
+        if neighbor_counts[idx] < 4 {
+            removed[idx] = true;
+            worklist.push(idx);
+        }
+    }
+
+    // removing a roll can only make its own neighbors accessible
+    let mut removed_rps = 0;
+    while let Some(pr_idx) = worklist.pop() {
+        removed_rps += 1;
+        for neighbor in map.neighbor_indices(pr_idx, true) {
+            if removed[neighbor] || !is_paper_roll(&map.tiles[neighbor]) {
+                continue;
+            }
+
+            neighbor_counts[neighbor] -= 1;
+            if neighbor_counts[neighbor] < 4 {
+                removed[neighbor] = true;
+                worklist.push(neighbor);
+            }
         }
     }
     Some(removed_rps)
@@ -0,0 +1,157 @@
+use std::{collections::HashMap, hash::Hash, mem};
+
+use super::Map2D;
+
+/// How [`Automaton::run`] stopped.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Outcome {
+    /// All the requested generations were computed.
+    Completed,
+    /// Nothing changed anymore after this generation.
+    FixedPoint(usize),
+    /// The state at generation `start + period` was already seen at generation `start`.
+    /// The remaining generations were skipped over by running only `remaining % period` of them.
+    Cycle { start: usize, period: usize },
+}
+
+/// A cellular automaton over a [`Map2D`].
+///
+/// Every generation, each tile is replaced by `rule(tile, n)` where `n` is the number of
+/// its neighbors for which `counted` returns `true`. All tiles are updated at once, using
+/// a second buffer that gets swapped with the map's tiles.
+pub struct Automaton<T, C, R> {
+    pub map: Map2D<T>,
+    buffer: Vec<T>,
+    include_corners: bool,
+    counted: C,
+    rule: R,
+    /// Number of generations computed so far.
+    pub generation: usize,
+    /// Number of tiles that changed, for every computed generation. Generations skipped over
+    /// by [`Automaton::run`] are not computed, so they have no entry.
+    pub changes: Vec<usize>,
+}
+
+impl<T, C, R> Automaton<T, C, R>
+where
+    T: Clone + PartialEq,
+    C: Fn(&T) -> bool,
+    R: Fn(&T, usize) -> T,
+{
+    pub fn new(map: Map2D<T>, include_corners: bool, counted: C, rule: R) -> Self {
+        Automaton {
+            buffer: map.tiles.clone(),
+            map,
+            include_corners,
+            counted,
+            rule,
+            generation: 0,
+            changes: vec![],
+        }
+    }
+
+    /// Computes the next generation and returns the number of tiles that changed.
+    pub fn step(&mut self) -> usize {
+        let mut changed = 0;
+        for idx in 0..self.map.tiles.len() {
+            let count = self
+                .map
+                .count_neighbors(idx, self.include_corners, &self.counted);
+            let next = (self.rule)(&self.map.tiles[idx], count);
+            if next != self.map.tiles[idx] {
+                changed += 1;
+            }
+            self.buffer[idx] = next;
+        }
+
+        mem::swap(&mut self.map.tiles, &mut self.buffer);
+        self.generation += 1;
+        self.changes.push(changed);
+        changed
+    }
+
+    /// Steps until a generation leaves the map unchanged, returns the number of steps taken
+    /// (the last, unchanged one included).
+    pub fn run_until_stable(&mut self) -> usize {
+        let start = self.generation;
+        while self.step() != 0 {}
+        self.generation - start
+    }
+}
+
+impl<T, C, R> Automaton<T, C, R>
+where
+    T: Clone + Eq + Hash,
+    C: Fn(&T) -> bool,
+    R: Fn(&T, usize) -> T,
+{
+    /// Advances the map to generation `target`, stopping early at a fixed point and
+    /// skipping ahead once a cycle is found.
+    ///
+    /// Every state is kept until a cycle is found, so that states are compared in full.
+    pub fn run(&mut self, target: usize) -> Outcome {
+        let mut seen = HashMap::from([(self.map.tiles.clone(), self.generation)]);
+
+        while self.generation < target {
+            if self.step() == 0 {
+                return Outcome::FixedPoint(self.generation - 1);
+            }
+
+            if let Some(&start) = seen.get(&self.map.tiles) {
+                let period = self.generation - start;
+                for _ in 0..(target - self.generation) % period {
+                    self.step();
+                }
+                self.generation = target;
+                return Outcome::Cycle { start, period };
+            }
+            seen.insert(self.map.tiles.clone(), self.generation);
+        }
+
+        Outcome::Completed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Automaton, Outcome};
+    use crate::utils::Map2D;
+
+    fn life(
+        map: Map2D<char>,
+    ) -> Automaton<char, impl Fn(&char) -> bool, impl Fn(&char, usize) -> char> {
+        Automaton::new(
+            map,
+            true,
+            |&c| c == '#',
+            |&c, n| match (c, n) {
+                ('#', 2 | 3) | ('.', 3) => '#',
+                _ => '.',
+            },
+        )
+    }
+
+    #[test]
+    fn detects_fixed_points() {
+        let block = Map2D::<char>::parse("....\n.##.\n.##.\n....\n", &[]).unwrap();
+        let mut automaton = life(block);
+        assert_eq!(automaton.run(100), Outcome::FixedPoint(0));
+        assert_eq!(automaton.changes, vec![0]);
+    }
+
+    #[test]
+    fn skips_ahead_on_cycles() {
+        let blinker = Map2D::<char>::parse(".....\n..#..\n..#..\n..#..\n.....\n", &[]).unwrap();
+        let mut automaton = life(blinker);
+        assert_eq!(
+            automaton.run(1_000_001),
+            Outcome::Cycle {
+                start: 0,
+                period: 2
+            }
+        );
+        assert_eq!(automaton.generation, 1_000_001);
+        assert_eq!(automaton.map.row(2).iter().collect::<String>(), ".###.");
+        assert_eq!(automaton.changes, vec![4, 4, 4]);
+    }
+}
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use super::Map2D;
+
+type Tile = usize;
+
+/// A maze collapsed into its junctions, see [`Map2D::junction_graph`].
+///
+/// Nodes are numbered from 0, every corridor appears once in the edge list of each of its
+/// ends, weighted by its length in steps.
+#[derive(Debug, Clone)]
+pub struct JunctionGraph {
+    /// Map tile of every node.
+    pub tiles: Vec<Tile>,
+    /// Outgoing `(node, length)` edges of every node.
+    pub edges: Vec<Vec<(usize, u64)>>,
+    nodes: HashMap<Tile, usize>,
+}
+
+impl JunctionGraph {
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Node lying on map tile `tile`, if any.
+    pub fn node_of(&self, tile: Tile) -> Option<usize> {
+        self.nodes.get(&tile).copied()
+    }
+
+    /// Edges of `node`, in the shape expected by [`super::search::dijkstra`].
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.edges[node].iter().copied()
+    }
+
+    /// Length of the longest path from `start` to `goal` that never visits a node twice.
+    ///
+    /// This is an exhaustive search, only tractable because the graph is small.
+    pub fn longest_path(&self, start: usize, goal: usize) -> Option<u64> {
+        let mut visited = vec![false; self.len()];
+        self.longest_from(start, goal, &mut visited)
+    }
+
+    fn longest_from(&self, node: usize, goal: usize, visited: &mut [bool]) -> Option<u64> {
+        if node == goal {
+            return Some(0);
+        }
+
+        visited[node] = true;
+        let longest = self.edges[node]
+            .iter()
+            .filter_map(|&(next, length)| {
+                if visited[next] {
+                    return None;
+                }
+                Some(length + self.longest_from(next, goal, visited)?)
+            })
+            .max();
+        visited[node] = false;
+        longest
+    }
+}
+
+impl<T> Map2D<T> {
+    /// Collapses every corridor of tiles matching `is_passable` into a single weighted edge.
+    ///
+    /// Nodes are junctions (3 or more passable neighbors), dead ends (at most 1) and every
+    /// tile in [`Map2D::markers`]. Corridors that loop back to where they started are dropped.
+    pub fn junction_graph(&self, is_passable: impl Fn(&T) -> bool) -> JunctionGraph {
+        let passable_neighbors = |idx: Tile| {
+            self.neighbor_indices(idx, false)
+                .filter(|&n| is_passable(&self.tiles[n]))
+        };
+
+        let mut tiles: Vec<Tile> = (0..self.tiles.len())
+            .filter(|&idx| is_passable(&self.tiles[idx]))
+            .filter(|&idx| passable_neighbors(idx).count() != 2)
+            .chain(self.markers.values().flatten().copied())
+            .collect();
+        tiles.sort_unstable();
+        tiles.dedup();
+        let nodes: HashMap<Tile, usize> = tiles.iter().enumerate().map(|(i, &t)| (t, i)).collect();
+
+        let edges = tiles
+            .iter()
+            .enumerate()
+            .map(|(node, &start)| {
+                passable_neighbors(start)
+                    .filter_map(|first| {
+                        // follow the corridor until the next node
+                        let (mut previous, mut current, mut length) = (start, first, 1);
+                        while !nodes.contains_key(&current) {
+                            let next = passable_neighbors(current).find(|&n| n != previous)?;
+                            (previous, current) = (current, next);
+                            length += 1;
+                        }
+                        let end = nodes[&current];
+                        (end != node).then_some((end, length))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        JunctionGraph {
+            tiles,
+            edges,
+            nodes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{Map2D, search::dijkstra};
+
+    #[test]
+    fn collapses_corridors() {
+        let map = Map2D::<char>::parse(
+            "#S#######\n#.......#\n#.#####.#\n#.......#\n#.#######\n#.......#\n#######E#\n",
+            &['S', 'E'],
+        )
+        .unwrap();
+        let graph = map.junction_graph(|&c| c != '#');
+        // S, E and both junctions on the left column
+        assert_eq!(graph.len(), 4);
+
+        let start = graph.node_of(map.marker('S').unwrap()).unwrap();
+        let goal = graph.node_of(map.marker('E').unwrap()).unwrap();
+        let result = dijkstra(start, |&n| graph.neighbors(n), |&n| n == goal);
+        assert_eq!(result.cost(), Some(1 + 2 + 9));
+        assert_eq!(graph.longest_path(start, goal), Some(1 + 14 + 9));
+    }
+}
@@ -0,0 +1,155 @@
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    hash::Hash,
+};
+
+/// States of a simulation up to the first repetition, see [`find_cycle`].
+///
+/// States from `start` on repeat every `period` steps forever, which lets [`Cycle::state_at`]
+/// answer for any step without simulating it.
+#[derive(Debug, Clone)]
+pub struct Cycle<S> {
+    /// Number of steps before entering the cycle.
+    pub start: usize,
+    pub period: usize,
+    /// Every state from step 0 to step `start + period - 1`.
+    pub states: Vec<S>,
+}
+
+impl<S> Cycle<S> {
+    /// Index in [`Cycle::states`] of the state reached after `n` steps.
+    pub fn index_at(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.index_at(n)]
+    }
+
+    /// Derives a value from the state reached after `n` steps, e.g. a score.
+    pub fn value_at<V>(&self, n: usize, value: impl Fn(&S) -> V) -> V {
+        value(self.state_at(n))
+    }
+}
+
+/// Runs `step` from `initial` until a state comes back, comparing states through `key`.
+///
+/// `key` can return the state itself (cloned) or any cheaper fingerprint that identifies it,
+/// such as the tiles of a [`super::Map2D`]. Every state is kept until the cycle closes.
+pub fn find_cycle<S, K>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    key: impl Fn(&S) -> K,
+) -> Cycle<S>
+where
+    K: Hash + Eq,
+{
+    let mut seen = HashMap::from([(key(&initial), 0)]);
+    let mut states = vec![initial];
+
+    loop {
+        let next = step(states.last().unwrap());
+        match seen.entry(key(&next)) {
+            Entry::Occupied(entry) => {
+                let start = *entry.get();
+                return Cycle {
+                    start,
+                    period: states.len() - start,
+                    states,
+                };
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(states.len());
+            }
+        }
+        states.push(next);
+    }
+}
+
+/// Brent's algorithm: returns `(start, period)` like [`find_cycle`], keeping only two states
+/// in memory at the cost of running `step` about three times as often.
+pub fn brent<S>(initial: S, mut step: impl FnMut(&S) -> S) -> (usize, usize)
+where
+    S: Clone + PartialEq,
+{
+    // find the period: the hare runs ahead while the tortoise teleports at powers of two
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // find the start: both walk together, `period` steps apart, until they meet
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    (start, period)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{brent, find_cycle};
+    use crate::utils::Map2D;
+
+    #[test]
+    fn finds_numeric_cycles() {
+        // powers of 3 modulo 100 come back to 3 after 20 steps
+        let step = |&x: &u64| x * 3 % 100;
+        let cycle = find_cycle(3, step, |&x| x);
+        assert_eq!((cycle.start, cycle.period), (0, 20));
+        assert_eq!(brent(3, step), (0, 20));
+
+        let step = |&x: &u64| (x * x + 1) % 255;
+        let cycle = find_cycle(3, step, |&x| x);
+        assert_eq!(brent(3, step), (cycle.start, cycle.period));
+        let mut x = 3;
+        for _ in 0..1000 {
+            x = step(&x);
+        }
+        assert_eq!(*cycle.state_at(1000), x);
+    }
+
+    #[test]
+    fn extrapolates_map_states() {
+        // a rock rolling east, that skips the first tile every time it wraps around
+        let map = Map2D::<char>::parse("O#...\n", &[]).unwrap();
+        let step = |map: &Map2D<char>| {
+            let mut next = map.clone();
+            next.tiles = match map.tiles.iter().position(|&c| c == 'O').unwrap() {
+                0 => "..O..".chars().collect(),
+                idx => {
+                    let mut tiles = vec!['.'; 5];
+                    tiles[(idx + 1) % 5 + usize::from(idx == 4)] = 'O';
+                    tiles
+                }
+            };
+            next
+        };
+
+        let cycle = find_cycle(map.clone(), step, |map| map.clone());
+        assert_eq!((cycle.start, cycle.period), (1, 4));
+        assert_eq!(brent(map, step), (1, 4));
+        let rock = |map: &Map2D<char>| map.tiles.iter().position(|&c| c == 'O');
+        assert_eq!(cycle.value_at(1_000_000_000, rock), Some(1));
+    }
+}
@@ -1,3 +1,7 @@
+use std::{error::Error, fmt::Display};
+
+use super::Pos2D;
+
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub enum Direction {
     North,
@@ -6,7 +10,35 @@ pub enum Direction {
     West,
 }
 
+/// A relative turn, as found in `L`/`R`/`U` movement instructions.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum Turn {
+    Left,
+    Right,
+    Around,
+}
+
+/// An error which can be returned when parsing a [`Direction`], [`Direction8`] or [`Turn`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ParseDirectionError(pub char);
+
+impl Error for ParseDirectionError {}
+
+impl Display for ParseDirectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid direction char: {}", self.0)
+    }
+}
+
 impl Direction {
+    /// All directions, clockwise from north.
+    pub const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::East,
+        Direction::South,
+        Direction::West,
+    ];
+
     pub fn rotate(&self) -> Direction {
         match self {
             Direction::North => Direction::East,
@@ -16,6 +48,32 @@ impl Direction {
         }
     }
 
+    pub fn rotate_ccw(&self) -> Direction {
+        match self {
+            Direction::North => Direction::West,
+            Direction::East => Direction::North,
+            Direction::South => Direction::East,
+            Direction::West => Direction::South,
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        self.rotate().rotate()
+    }
+
+    pub fn turn(&self, turn: Turn) -> Direction {
+        match turn {
+            Turn::Left => self.rotate_ccw(),
+            Turn::Right => self.rotate(),
+            Turn::Around => self.opposite(),
+        }
+    }
+
+    /// Unit step towards the direction, with north being up (negative y).
+    pub fn delta(&self) -> Pos2D {
+        Pos2D::from(*self)
+    }
+
     pub fn from_char(c: char) -> Self {
         match c {
             '^' => Direction::North,
@@ -35,3 +93,181 @@ impl Direction {
         }
     }
 }
+
+impl TryFrom<char> for Direction {
+    type Error = ParseDirectionError;
+
+    /// Accepts arrows (`^>v<`), `UDLR` and `NESW` letters.
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            '^' | 'U' | 'N' => Ok(Direction::North),
+            '>' | 'R' | 'E' => Ok(Direction::East),
+            'v' | 'D' | 'S' => Ok(Direction::South),
+            '<' | 'L' | 'W' => Ok(Direction::West),
+            _ => Err(ParseDirectionError(value)),
+        }
+    }
+}
+
+impl TryFrom<char> for Turn {
+    type Error = ParseDirectionError;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'L' => Ok(Turn::Left),
+            'R' => Ok(Turn::Right),
+            'U' => Ok(Turn::Around),
+            _ => Err(ParseDirectionError(value)),
+        }
+    }
+}
+
+/// A cardinal or diagonal direction.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    /// All directions, clockwise from north.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// Rotates by 45° clockwise.
+    pub fn rotate(&self) -> Direction8 {
+        Self::ALL[(self.index() + 1) % 8]
+    }
+
+    /// Rotates by 45° counter-clockwise.
+    pub fn rotate_ccw(&self) -> Direction8 {
+        Self::ALL[(self.index() + 7) % 8]
+    }
+
+    pub fn opposite(&self) -> Direction8 {
+        Self::ALL[(self.index() + 4) % 8]
+    }
+
+    /// Turns by 90°, like [`Direction::turn`].
+    pub fn turn(&self, turn: Turn) -> Direction8 {
+        match turn {
+            Turn::Left => Self::ALL[(self.index() + 6) % 8],
+            Turn::Right => Self::ALL[(self.index() + 2) % 8],
+            Turn::Around => self.opposite(),
+        }
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    /// Unit step towards the direction, with north being up (negative y).
+    pub fn delta(&self) -> Pos2D {
+        match self {
+            Direction8::North => Pos2D::new(0, -1),
+            Direction8::NorthEast => Pos2D::new(1, -1),
+            Direction8::East => Pos2D::new(1, 0),
+            Direction8::SouthEast => Pos2D::new(1, 1),
+            Direction8::South => Pos2D::new(0, 1),
+            Direction8::SouthWest => Pos2D::new(-1, 1),
+            Direction8::West => Pos2D::new(-1, 0),
+            Direction8::NorthWest => Pos2D::new(-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::North => Direction8::North,
+            Direction::East => Direction8::East,
+            Direction::South => Direction8::South,
+            Direction::West => Direction8::West,
+        }
+    }
+}
+
+impl TryFrom<Direction8> for Direction {
+    type Error = Direction8;
+
+    /// Fails for diagonal directions.
+    fn try_from(value: Direction8) -> Result<Self, Self::Error> {
+        match value {
+            Direction8::North => Ok(Direction::North),
+            Direction8::East => Ok(Direction::East),
+            Direction8::South => Ok(Direction::South),
+            Direction8::West => Ok(Direction::West),
+            _ => Err(value),
+        }
+    }
+}
+
+impl TryFrom<char> for Direction8 {
+    type Error = ParseDirectionError;
+
+    /// Accepts the same chars as [`Direction`], which are never diagonal.
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        Direction::try_from(value).map(Direction8::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Direction8, Turn};
+    use crate::utils::Pos2D;
+
+    #[test]
+    fn turns() {
+        assert_eq!(Direction::North.turn(Turn::Left), Direction::West);
+        assert_eq!(Direction::North.turn(Turn::Right), Direction::East);
+        assert_eq!(Direction::East.turn(Turn::Around), Direction::West);
+        assert_eq!(
+            Direction8::NorthEast.turn(Turn::Left),
+            Direction8::NorthWest
+        );
+        assert_eq!(Direction8::West.rotate(), Direction8::NorthWest);
+        assert_eq!(Direction8::North.rotate_ccw(), Direction8::NorthWest);
+    }
+
+    #[test]
+    fn parses_chars() {
+        for (chars, dir) in ["^UN", ">RE", "vDS", "<LW"].iter().zip(Direction::ALL) {
+            for c in chars.chars() {
+                assert_eq!(Direction::try_from(c), Ok(dir));
+            }
+        }
+        assert!(Direction::try_from('x').is_err());
+        assert_eq!(Turn::try_from('U'), Ok(Turn::Around));
+    }
+
+    #[test]
+    fn deltas_are_consistent() {
+        for dir in Direction8::ALL {
+            let delta = dir.delta();
+            assert_eq!(Pos2D::new(0, 0).get_direction(delta), Some(dir));
+            assert_eq!(dir.opposite().delta(), -delta);
+            assert_eq!(dir.is_diagonal(), delta.x != 0 && delta.y != 0);
+        }
+        for dir in Direction::ALL {
+            assert_eq!(Direction8::from(dir).delta(), dir.delta());
+        }
+    }
+}
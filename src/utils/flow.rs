@@ -0,0 +1,318 @@
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+use super::Graph;
+
+/// A partition of a graph's nodes in two sides, see [`Graph::max_flow`] and [`Graph::min_cut`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cut {
+    /// Total weight of the cut edges, which is also the maximum flow for [`Graph::max_flow`].
+    pub weight: u64,
+    /// Side of every node: `true` for the source side of [`Graph::max_flow`], either side
+    /// for [`Graph::min_cut`].
+    pub side: Vec<bool>,
+    /// Edges going from one side to the other, as `(from, to)` node ids.
+    pub edges: Vec<(usize, usize)>,
+}
+
+impl Cut {
+    fn new<K>(graph: &Graph<K>, weight: u64, side: Vec<bool>) -> Self {
+        let edges = graph
+            .edges()
+            .filter(|&(a, b, _)| {
+                if graph.directed {
+                    side[a] && !side[b]
+                } else {
+                    side[a] != side[b]
+                }
+            })
+            .map(|(a, b, _)| (a, b))
+            .collect();
+        Cut {
+            weight,
+            side,
+            edges,
+        }
+    }
+
+    /// Number of nodes on the `true` side, then on the `false` side.
+    pub fn sizes(&self) -> (usize, usize) {
+        let inside = self.side.iter().filter(|&&side| side).count();
+        (inside, self.side.len() - inside)
+    }
+}
+
+/// Residual network for Dinic's algorithm. Arc `i ^ 1` is the reverse of arc `i`.
+struct Network {
+    to: Vec<usize>,
+    capacity: Vec<u64>,
+    arcs: Vec<Vec<usize>>,
+    level: Vec<Option<usize>>,
+    next_arc: Vec<usize>,
+}
+
+impl Network {
+    fn new<K>(graph: &Graph<K>) -> Self {
+        let mut network = Network {
+            to: vec![],
+            capacity: vec![],
+            arcs: vec![vec![]; graph.len()],
+            level: vec![],
+            next_arc: vec![],
+        };
+        // undirected edges are already stored both ways, each becoming its own arc
+        for (a, edges) in graph.adjacency.iter().enumerate() {
+            for &(b, weight) in edges {
+                network.add_arc(a, b, weight);
+            }
+        }
+        network
+    }
+
+    fn add_arc(&mut self, a: usize, b: usize, capacity: u64) {
+        self.arcs[a].push(self.to.len());
+        self.to.push(b);
+        self.capacity.push(capacity);
+        self.arcs[b].push(self.to.len());
+        self.to.push(a);
+        self.capacity.push(0);
+    }
+
+    /// Levels every node reachable from `source` through arcs with capacity left.
+    fn bfs(&mut self, source: usize) {
+        self.level = vec![None; self.arcs.len()];
+        self.level[source] = Some(0);
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            for &arc in &self.arcs[node] {
+                let next = self.to[arc];
+                if self.capacity[arc] > 0 && self.level[next].is_none() {
+                    self.level[next] = Some(self.level[node].unwrap() + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    /// Pushes flow along one path of increasing levels, returning how much went through.
+    ///
+    /// The path is walked with an explicit stack of arcs, so deep level graphs can't
+    /// overflow the call stack.
+    fn augment(&mut self, source: usize, sink: usize) -> u64 {
+        let mut path: Vec<usize> = vec![];
+        let mut node = source;
+        loop {
+            if node == sink {
+                let pushed = path.iter().map(|&arc| self.capacity[arc]).min().unwrap();
+                for &arc in &path {
+                    self.capacity[arc] -= pushed;
+                    self.capacity[arc ^ 1] += pushed;
+                }
+                return pushed;
+            }
+
+            let level = self.level[node].map(|l| l + 1);
+            let next_arc = self.arcs[node][self.next_arc[node]..]
+                .iter()
+                .position(|&arc| self.capacity[arc] > 0 && self.level[self.to[arc]] == level);
+            match next_arc {
+                Some(offset) => {
+                    self.next_arc[node] += offset;
+                    let arc = self.arcs[node][self.next_arc[node]];
+                    path.push(arc);
+                    node = self.to[arc];
+                }
+                None => {
+                    // dead end: never come back here, and move the previous node past it
+                    self.next_arc[node] = self.arcs[node].len();
+                    let Some(arc) = path.pop() else {
+                        return 0;
+                    };
+                    node = self.to[arc ^ 1];
+                    self.next_arc[node] += 1;
+                }
+            }
+        }
+    }
+
+    fn max_flow(&mut self, source: usize, sink: usize) -> u64 {
+        let mut flow = 0;
+        loop {
+            self.bfs(source);
+            if self.level[sink].is_none() {
+                return flow;
+            }
+            self.next_arc = vec![0; self.arcs.len()];
+            loop {
+                let pushed = self.augment(source, sink);
+                if pushed == 0 {
+                    break;
+                }
+                flow += pushed;
+            }
+        }
+    }
+}
+
+impl<K> Graph<K> {
+    /// Maximum flow from `source` to `sink` (Dinic's algorithm), using edge weights as
+    /// capacities. Undirected edges carry flow both ways.
+    ///
+    /// The returned cut is a minimum `source`-`sink` cut: `side` marks the nodes still
+    /// reachable from `source` in the residual network. `None` if `source` and `sink` are
+    /// the same node or aren't in the graph.
+    pub fn max_flow(&self, source: usize, sink: usize) -> Option<Cut> {
+        if source == sink || source >= self.len() || sink >= self.len() {
+            return None;
+        }
+        let mut network = Network::new(self);
+        let flow = network.max_flow(source, sink);
+        // the last BFS found every node reachable from the source
+        let side = network.level.iter().map(Option::is_some).collect();
+        Some(Cut::new(self, flow, side))
+    }
+
+    /// Lightest set of edges whose removal splits the graph in two (Stoer–Wagner), edge
+    /// directions being ignored. `None` if there are fewer than 2 nodes.
+    pub fn min_cut(&self) -> Option<Cut> {
+        if self.len() < 2 {
+            return None;
+        }
+
+        // merged nodes: weights towards the other ones, and the original nodes they hold
+        let mut weights: Vec<HashMap<usize, u64>> = vec![HashMap::new(); self.len()];
+        for (a, b, weight) in self.edges() {
+            if a != b {
+                *weights[a].entry(b).or_default() += weight;
+                *weights[b].entry(a).or_default() += weight;
+            }
+        }
+        let mut members: Vec<Vec<usize>> = (0..self.len()).map(|id| vec![id]).collect();
+        let mut active: Vec<usize> = (0..self.len()).collect();
+        let mut best: Option<(u64, Vec<usize>)> = None;
+
+        while active.len() > 1 {
+            // maximum adjacency ordering, with a lazy max-heap
+            let mut added = vec![false; self.len()];
+            let mut connection = vec![0; self.len()];
+            let mut heap = BinaryHeap::from([(0, active[0])]);
+            let mut order = vec![];
+
+            while let Some((weight, node)) = heap.pop() {
+                if added[node] || weight != connection[node] {
+                    continue;
+                }
+                added[node] = true;
+                order.push(node);
+                for (&next, &w) in &weights[node] {
+                    if !added[next] {
+                        connection[next] += w;
+                        heap.push((connection[next], next));
+                    }
+                }
+            }
+
+            if order.len() < active.len() {
+                // disconnected graph: whatever wasn't reached is a free cut
+                let mut side = vec![true; self.len()];
+                for id in order.iter().flat_map(|&node| &members[node]) {
+                    side[*id] = false;
+                }
+                return Some(Cut::new(self, 0, side));
+            }
+
+            let (s, t) = (order[order.len() - 2], order[order.len() - 1]);
+            if best
+                .as_ref()
+                .is_none_or(|(weight, _)| connection[t] < *weight)
+            {
+                best = Some((connection[t], members[t].clone()));
+            }
+
+            // merge t into s
+            let merged = std::mem::take(&mut weights[t]);
+            for (next, w) in merged {
+                weights[next].remove(&t);
+                if next != s {
+                    *weights[s].entry(next).or_default() += w;
+                    *weights[next].entry(s).or_default() += w;
+                }
+            }
+            let moved = std::mem::take(&mut members[t]);
+            members[s].extend(moved);
+            active.retain(|&node| node != t);
+        }
+
+        let (weight, nodes) = best?;
+        let mut side = vec![false; self.len()];
+        for id in nodes {
+            side[id] = true;
+        }
+        Some(Cut::new(self, weight, side))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::Graph;
+
+    const WIRES: &str = "jqt: rhn xhk nvd\nrsh: frs pzl lsr\nxhk: hfx\ncmg: qnr nvd lhk bvb\nrhn: xhk bvb hfx\nbvb: xhk hfx\npzl: lsr hfx nvd\nqnr: nvd\nntq: jqt hfx bvb xhk\nnvd: lhk\nlsr: lhk\nrzs: qnr cmg lsr rsh\nfrs: qnr lhk lsr\n";
+
+    #[test]
+    fn finds_global_min_cut() {
+        let graph = Graph::parse(WIRES, false);
+        let cut = graph.min_cut().unwrap();
+        assert_eq!(cut.weight, 3);
+        let (a, b) = cut.sizes();
+        assert_eq!(a * b, 54);
+
+        let mut edges: Vec<[&str; 2]> = cut
+            .edges
+            .iter()
+            .map(|&(a, b)| {
+                let mut pair = [graph.key(a).as_str(), graph.key(b).as_str()];
+                pair.sort();
+                pair
+            })
+            .collect();
+        edges.sort();
+        assert_eq!(edges, [["bvb", "cmg"], ["hfx", "pzl"], ["jqt", "nvd"]]);
+    }
+
+    #[test]
+    fn computes_max_flow() {
+        let graph = Graph::parse(WIRES, false);
+        let id = |key: &str| graph.id(&key.to_string()).unwrap();
+        let cut = graph.max_flow(id("jqt"), id("cmg")).unwrap();
+        assert_eq!((cut.weight, cut.edges.len()), (3, 3));
+        assert_eq!(cut.sizes(), (6, 9));
+
+        let mut directed = Graph::new(true);
+        for (a, b, capacity) in [(0, 1, 3), (0, 2, 2), (1, 2, 5), (1, 3, 2), (2, 3, 3)] {
+            directed.add_weighted_edge(a, b, capacity);
+        }
+        let cut = directed.max_flow(0, 3).unwrap();
+        assert_eq!(cut.weight, 5);
+        assert_eq!(cut.side, [true, false, false, false]);
+        assert_eq!(cut.edges, [(0, 1), (0, 2)]);
+    }
+
+    #[test]
+    fn rejects_degenerate_flows() {
+        let mut graph = Graph::new(true);
+        graph.add_weighted_edge(0, 1, 3);
+        assert!(graph.max_flow(0, 0).is_none());
+        assert!(graph.max_flow(0, 2).is_none());
+        assert_eq!(graph.max_flow(1, 0).map(|cut| cut.weight), Some(0));
+    }
+
+    #[test]
+    fn handles_deep_level_graphs() {
+        // one level per node, way deeper than a recursive search could go
+        let mut chain = Graph::new(true);
+        for node in 0..200_000 {
+            chain.add_weighted_edge(node, node + 1, 2);
+        }
+        let cut = chain.max_flow(0, 200_000).unwrap();
+        assert_eq!((cut.weight, cut.edges.len()), (2, 1));
+    }
+}
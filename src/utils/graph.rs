@@ -0,0 +1,442 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt::{Display, Write},
+    hash::Hash,
+};
+
+/// An adjacency-list graph whose nodes are interned keys (names, numbers, ...).
+///
+/// Nodes are numbered from 0 in insertion order, every algorithm works on those ids and
+/// [`Graph::key`] maps them back. Edges carry a weight, 1 unless given.
+#[derive(Debug, Clone)]
+pub struct Graph<K> {
+    pub directed: bool,
+    keys: Vec<K>,
+    ids: HashMap<K, usize>,
+    /// Outgoing `(node, weight)` edges of every node. Undirected edges are stored both ways.
+    pub adjacency: Vec<Vec<(usize, u64)>>,
+}
+
+/// Returned by [`Graph::topological_sort`] and [`Graph::count_paths`] when the graph isn't
+/// acyclic. Holds the ids of the nodes forming one of the cycles, in order.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CycleError {
+    pub cycle: Vec<usize>,
+}
+
+impl Error for CycleError {}
+
+impl Display for CycleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "graph has a cycle through nodes {:?}", self.cycle)
+    }
+}
+
+impl<K> Graph<K>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new(directed: bool) -> Self {
+        Graph {
+            directed,
+            keys: vec![],
+            ids: HashMap::new(),
+            adjacency: vec![],
+        }
+    }
+
+    pub fn from_edges(edges: impl IntoIterator<Item = (K, K)>, directed: bool) -> Self {
+        let mut graph = Graph::new(directed);
+        for (a, b) in edges {
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+
+    /// Returns the id of `key`, adding a node for it if needed.
+    pub fn intern(&mut self, key: K) -> usize {
+        if let Some(&id) = self.ids.get(&key) {
+            return id;
+        }
+        let id = self.keys.len();
+        self.ids.insert(key.clone(), id);
+        self.keys.push(key);
+        self.adjacency.push(vec![]);
+        id
+    }
+
+    pub fn add_edge(&mut self, a: K, b: K) {
+        self.add_weighted_edge(a, b, 1);
+    }
+
+    pub fn add_weighted_edge(&mut self, a: K, b: K, weight: u64) {
+        let (a, b) = (self.intern(a), self.intern(b));
+        self.adjacency[a].push((b, weight));
+        if !self.directed && a != b {
+            self.adjacency[b].push((a, weight));
+        }
+    }
+
+    pub fn id(&self, key: &K) -> Option<usize> {
+        self.ids.get(key).copied()
+    }
+}
+
+impl<K> Graph<K> {
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn key(&self, id: usize) -> &K {
+        &self.keys[id]
+    }
+
+    pub fn keys(&self) -> &[K] {
+        &self.keys
+    }
+
+    pub fn neighbors(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.adjacency[id].iter().map(|&(next, _)| next)
+    }
+
+    /// Every edge as `(from, to, weight)`. Undirected edges are only listed once.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, u64)> + '_ {
+        self.adjacency
+            .iter()
+            .enumerate()
+            .flat_map(move |(a, edges)| {
+                edges
+                    .iter()
+                    .filter(move |&&(b, _)| self.directed || a <= b)
+                    .map(move |&(b, weight)| (a, b, weight))
+            })
+    }
+
+    fn reversed(&self) -> Vec<Vec<usize>> {
+        let mut reversed = vec![vec![]; self.len()];
+        for (a, edges) in self.adjacency.iter().enumerate() {
+            for &(b, _) in edges {
+                reversed[b].push(a);
+            }
+        }
+        reversed
+    }
+
+    /// Orders the nodes so that every edge goes forward (Kahn's algorithm).
+    ///
+    /// Undirected edges count as 2-node cycles, so this only makes sense for directed graphs.
+    pub fn topological_sort(&self) -> Result<Vec<usize>, CycleError> {
+        let mut in_degree = vec![0; self.len()];
+        for id in self.adjacency.iter().flatten().map(|&(next, _)| next) {
+            in_degree[id] += 1;
+        }
+
+        let mut queue: VecDeque<usize> = (0..self.len()).filter(|&id| in_degree[id] == 0).collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(id) = queue.pop_front() {
+            order.push(id);
+            for next in self.neighbors(id) {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 {
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if order.len() == self.len() {
+            return Ok(order);
+        }
+
+        // every node left has a predecessor that is also left, walking back through them
+        // eventually comes across a node twice
+        let reversed = self.reversed();
+        let mut current = (0..self.len()).find(|&id| in_degree[id] > 0).unwrap();
+        let mut seen = vec![None; self.len()];
+        let mut walk = vec![];
+        while seen[current].is_none() {
+            seen[current] = Some(walk.len());
+            walk.push(current);
+            current = reversed[current]
+                .iter()
+                .copied()
+                .find(|&prev| in_degree[prev] > 0)
+                .unwrap();
+        }
+        let mut cycle = walk.split_off(seen[current].unwrap());
+        cycle.reverse();
+        Err(CycleError { cycle })
+    }
+
+    /// Strongly connected components (Kosaraju's algorithm), in topological order of the
+    /// condensed graph.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        // nodes by increasing DFS finish time
+        let mut finished = Vec::with_capacity(self.len());
+        let mut visited = vec![false; self.len()];
+        for root in 0..self.len() {
+            if visited[root] {
+                continue;
+            }
+            visited[root] = true;
+            let mut stack = vec![(root, 0)];
+            while let Some((id, edge)) = stack.pop() {
+                match self.adjacency[id].get(edge) {
+                    Some(&(next, _)) => {
+                        stack.push((id, edge + 1));
+                        if !visited[next] {
+                            visited[next] = true;
+                            stack.push((next, 0));
+                        }
+                    }
+                    None => finished.push(id),
+                }
+            }
+        }
+
+        let reversed = self.reversed();
+        let mut component = vec![None; self.len()];
+        let mut components = vec![];
+        for &root in finished.iter().rev() {
+            if component[root].is_some() {
+                continue;
+            }
+            component[root] = Some(components.len());
+            let mut members = vec![root];
+            let mut stack = vec![root];
+            while let Some(id) = stack.pop() {
+                for &prev in &reversed[id] {
+                    if component[prev].is_none() {
+                        component[prev] = Some(components.len());
+                        members.push(prev);
+                        stack.push(prev);
+                    }
+                }
+            }
+            components.push(members);
+        }
+        components
+    }
+
+    /// A largest set of nodes all connected to each other (Bron–Kerbosch with pivoting).
+    ///
+    /// Edge directions are ignored.
+    pub fn max_clique(&self) -> Vec<usize> {
+        let mut neighbors: Vec<HashSet<usize>> = vec![HashSet::new(); self.len()];
+        for (a, b, _) in self.edges() {
+            if a != b {
+                neighbors[a].insert(b);
+                neighbors[b].insert(a);
+            }
+        }
+
+        let mut best = vec![];
+        bron_kerbosch(
+            &neighbors,
+            &mut vec![],
+            (0..self.len()).collect(),
+            HashSet::new(),
+            &mut best,
+        );
+        best
+    }
+
+    /// Number of distinct paths from `from` to `to`, memoized per node.
+    ///
+    /// Fails if a cycle can be reached from `from`, since there would be infinitely many.
+    pub fn count_paths(&self, from: usize, to: usize) -> Result<u64, CycleError> {
+        let mut memo = vec![None; self.len()];
+        let mut path = vec![];
+        self.count_paths_from(from, to, &mut memo, &mut path)
+    }
+
+    fn count_paths_from(
+        &self,
+        id: usize,
+        to: usize,
+        memo: &mut [Option<u64>],
+        path: &mut Vec<usize>,
+    ) -> Result<u64, CycleError> {
+        if id == to {
+            return Ok(1);
+        }
+        if let Some(count) = memo[id] {
+            return Ok(count);
+        }
+        if let Some(start) = path.iter().position(|&p| p == id) {
+            return Err(CycleError {
+                cycle: path[start..].to_vec(),
+            });
+        }
+
+        path.push(id);
+        let mut count = 0;
+        for next in self.neighbors(id) {
+            count += self.count_paths_from(next, to, memo, path)?;
+        }
+        path.pop();
+        memo[id] = Some(count);
+        Ok(count)
+    }
+}
+
+fn bron_kerbosch(
+    neighbors: &[HashSet<usize>],
+    clique: &mut Vec<usize>,
+    mut candidates: HashSet<usize>,
+    mut excluded: HashSet<usize>,
+    best: &mut Vec<usize>,
+) {
+    if candidates.is_empty() {
+        if excluded.is_empty() && clique.len() > best.len() {
+            *best = clique.clone();
+        }
+        return;
+    }
+    if clique.len() + candidates.len() <= best.len() {
+        return;
+    }
+
+    // no need to try the pivot's neighbors, a maximal clique holds the pivot or a non-neighbor
+    let pivot = candidates
+        .union(&excluded)
+        .max_by_key(|&&id| neighbors[id].intersection(&candidates).count())
+        .copied()
+        .unwrap();
+    let tried: Vec<usize> = candidates.difference(&neighbors[pivot]).copied().collect();
+
+    for id in tried {
+        clique.push(id);
+        bron_kerbosch(
+            neighbors,
+            clique,
+            candidates.intersection(&neighbors[id]).copied().collect(),
+            excluded.intersection(&neighbors[id]).copied().collect(),
+            best,
+        );
+        clique.pop();
+        candidates.remove(&id);
+        excluded.insert(id);
+    }
+}
+
+impl<K: Display> Graph<K> {
+    /// Graphviz DOT description of the graph, weights other than 1 become edge labels.
+    pub fn to_dot(&self) -> String {
+        let (kind, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+
+        let mut out = format!("{kind} {{\n");
+        for key in &self.keys {
+            writeln!(out, "  \"{key}\";").unwrap();
+        }
+        for (a, b, weight) in self.edges() {
+            write!(out, "  \"{}\" {arrow} \"{}\"", self.keys[a], self.keys[b]).unwrap();
+            if weight != 1 {
+                write!(out, " [label=\"{weight}\"]").unwrap();
+            }
+            out.push_str(";\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+impl Graph<String> {
+    /// Parses one edge list per line, in any of these forms:
+    ///
+    /// - `a: b c d` or `a -> b, c`: edges from `a` to every listed node
+    /// - `a-b` or `a b`: a single edge
+    pub fn parse(input: &str, directed: bool) -> Self {
+        let mut graph = Graph::new(directed);
+        for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let split = line.split_once("->").or_else(|| line.split_once(':'));
+            let (from, targets): (&str, Vec<&str>) = match split {
+                Some((from, rest)) => (
+                    from,
+                    rest.split([' ', ',']).filter(|s| !s.is_empty()).collect(),
+                ),
+                None => match line.split_once(['-', ' ']) {
+                    Some((a, b)) => (a, vec![b]),
+                    None => (line, vec![]),
+                },
+            };
+
+            // interned up front so that nodes without edges still exist
+            let from = from.trim().to_string();
+            graph.intern(from.clone());
+            for target in targets {
+                graph.add_edge(from.clone(), target.trim().to_string());
+            }
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Graph;
+
+    #[test]
+    fn sorts_and_counts_paths() {
+        let graph = Graph::parse(
+            "svr: aaa bbb\naaa: fft\nfft: ccc\nbbb: tty\ntty: ccc\nccc: ddd eee\nddd: hub\nhub: fff\neee: dac\ndac: fff\nfff: ggg hhh\nggg: out\nhhh: out\n",
+            true,
+        );
+        let order = graph.topological_sort().unwrap();
+        let position = |key: &str| {
+            let id = graph.id(&key.to_string()).unwrap();
+            order.iter().position(|&o| o == id).unwrap()
+        };
+        assert!(position("svr") < position("ccc") && position("ccc") < position("out"));
+
+        let id = |key: &str| graph.id(&key.to_string()).unwrap();
+        assert_eq!(graph.count_paths(id("svr"), id("out")), Ok(8));
+        assert_eq!(graph.count_paths(id("fft"), id("dac")), Ok(1));
+        assert_eq!(graph.count_paths(id("dac"), id("fft")), Ok(0));
+    }
+
+    #[test]
+    fn reports_cycles_and_components() {
+        let graph = Graph::from_edges([(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 4)], true);
+        let cycle = graph.topological_sort().unwrap_err().cycle;
+        assert!(cycle.len() == 2 || cycle.len() == 3);
+        assert!(graph.count_paths(0, 4).is_err());
+
+        let components: Vec<Vec<u32>> = graph
+            .strongly_connected_components()
+            .into_iter()
+            .map(|c| {
+                let mut keys: Vec<u32> = c.into_iter().map(|id| *graph.key(id)).collect();
+                keys.sort();
+                keys
+            })
+            .collect();
+        assert_eq!(components, vec![vec![1, 2, 3], vec![4, 5]]);
+
+        assert!(graph.to_dot().starts_with("digraph {\n  \"1\";"));
+        assert!(graph.to_dot().contains("\"3\" -> \"4\";"));
+    }
+
+    #[test]
+    fn finds_max_clique() {
+        let graph = Graph::parse(
+            "ka-co\nta-co\nde-co\nta-ka\nde-ta\nka-de\nqp-td\ntd-yn\nqp-ka\nwh-yn\n",
+            false,
+        );
+        let mut clique: Vec<&str> = graph
+            .max_clique()
+            .into_iter()
+            .map(|id| graph.key(id).as_str())
+            .collect();
+        clique.sort();
+        assert_eq!(clique, ["co", "de", "ka", "ta"]);
+    }
+}
@@ -0,0 +1,375 @@
+use std::{
+    collections::{HashMap, HashSet, hash_map},
+    error::Error,
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, Sub},
+};
+
+/// A hexagon in axial coordinates. The third cube coordinate is [`Hex::s`].
+///
+/// Neighbors are the same for both layouts, only the names of the directions differ,
+/// see [`HexLayout`].
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+/// How hexagons are drawn, which decides the names of the 6 directions.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum HexLayout {
+    /// Pointy top: neighbors lie `e`, `ne`, `nw`, `w`, `sw` and `se`.
+    Pointy,
+    /// Flat top: neighbors lie `n`, `ne`, `se`, `s`, `sw` and `nw`.
+    Flat,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub enum HexDirection {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+/// An error which can be returned when parsing hex steps.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseHexError(pub String);
+
+impl Error for ParseHexError {}
+
+impl Display for ParseHexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid hex step: {}", self.0)
+    }
+}
+
+/// Axial offsets of the 6 neighbors, shared by both layouts.
+const NEIGHBORS: [(i32, i32); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+impl Hex {
+    pub const fn new(q: i32, r: i32) -> Hex {
+        Hex { q, r }
+    }
+
+    /// Builds a hexagon from cube coordinates, which must sum to 0.
+    pub fn from_cube(q: i32, r: i32, s: i32) -> Hex {
+        debug_assert_eq!(q + r + s, 0, "cube coordinates must sum to 0");
+        Hex::new(q, r)
+    }
+
+    pub fn s(&self) -> i32 {
+        -self.q - self.r
+    }
+
+    pub fn to_cube(&self) -> (i32, i32, i32) {
+        (self.q, self.r, self.s())
+    }
+
+    pub fn neighbors(&self) -> [Hex; 6] {
+        NEIGHBORS.map(|(dq, dr)| Hex::new(self.q + dq, self.r + dr))
+    }
+
+    /// Number of steps between both hexagons.
+    pub fn dist(&self, other: &Hex) -> i32 {
+        let d = *other - *self;
+        (d.q.abs() + d.r.abs() + d.s().abs()) / 2
+    }
+
+    /// Moves one step towards `direction`, which must exist in `layout`.
+    pub fn step(&self, direction: HexDirection, layout: HexLayout) -> Option<Hex> {
+        Some(*self + direction.delta(layout)?)
+    }
+
+    /// Follows every step from `self`, returning where it ends.
+    pub fn walk(&self, steps: &[HexDirection], layout: HexLayout) -> Option<Hex> {
+        steps
+            .iter()
+            .try_fold(*self, |hex, &direction| hex.step(direction, layout))
+    }
+}
+
+impl Add for Hex {
+    type Output = Hex;
+
+    fn add(self, rhs: Hex) -> Self::Output {
+        Hex::new(self.q + rhs.q, self.r + rhs.r)
+    }
+}
+
+impl AddAssign for Hex {
+    fn add_assign(&mut self, rhs: Hex) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Hex {
+    type Output = Hex;
+
+    fn sub(self, rhs: Hex) -> Self::Output {
+        Hex::new(self.q - rhs.q, self.r - rhs.r)
+    }
+}
+
+impl Mul<i32> for Hex {
+    type Output = Hex;
+
+    fn mul(self, rhs: i32) -> Self::Output {
+        Hex::new(self.q * rhs, self.r * rhs)
+    }
+}
+
+impl HexLayout {
+    /// The 6 directions of the layout, counter-clockwise from the first one past east.
+    pub fn directions(&self) -> [HexDirection; 6] {
+        use HexDirection::*;
+        match self {
+            HexLayout::Pointy => [East, NorthEast, NorthWest, West, SouthWest, SouthEast],
+            HexLayout::Flat => [NorthEast, North, NorthWest, SouthWest, South, SouthEast],
+        }
+    }
+
+    /// Parses a list of steps such as `ne,ne,s,s` or `esenee`, with optional separators.
+    ///
+    /// Steps are separated by commas or whitespace, and runs of letters without separators
+    /// are split greedily, two letter directions first.
+    pub fn parse_steps(&self, input: &str) -> Result<Vec<HexDirection>, ParseHexError> {
+        let mut steps = vec![];
+        for run in input
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|run| !run.is_empty())
+        {
+            if !run.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(ParseHexError(run.to_string()));
+            }
+            let letters: Vec<char> = run.chars().map(|c| c.to_ascii_lowercase()).collect();
+
+            let mut i = 0;
+            while i < letters.len() {
+                // prefer two letter directions, they are never a prefix of a valid one letter step
+                let two = letters.get(i..i + 2).and_then(|pair| self.direction(pair));
+                let (direction, len) = match two {
+                    Some(direction) => (direction, 2),
+                    None => (
+                        self.direction(&letters[i..i + 1])
+                            .ok_or_else(|| ParseHexError(letters[i..].iter().collect()))?,
+                        1,
+                    ),
+                };
+                steps.push(direction);
+                i += len;
+            }
+        }
+        Ok(steps)
+    }
+
+    fn direction(&self, letters: &[char]) -> Option<HexDirection> {
+        use HexDirection::*;
+        let direction = match letters {
+            ['n'] => North,
+            ['n', 'e'] => NorthEast,
+            ['e'] => East,
+            ['s', 'e'] => SouthEast,
+            ['s'] => South,
+            ['s', 'w'] => SouthWest,
+            ['w'] => West,
+            ['n', 'w'] => NorthWest,
+            _ => return None,
+        };
+        direction.delta(*self).map(|_| direction)
+    }
+}
+
+impl HexDirection {
+    /// Axial offset of the direction, `None` if it doesn't exist in `layout`
+    /// (e.g. `North` with pointy tops).
+    pub fn delta(&self, layout: HexLayout) -> Option<Hex> {
+        use HexDirection::*;
+        let (dq, dr) = match (layout, self) {
+            (HexLayout::Pointy, East) => (1, 0),
+            (HexLayout::Pointy, NorthEast) => (1, -1),
+            (HexLayout::Pointy, NorthWest) => (0, -1),
+            (HexLayout::Pointy, West) => (-1, 0),
+            (HexLayout::Pointy, SouthWest) => (-1, 1),
+            (HexLayout::Pointy, SouthEast) => (0, 1),
+            (HexLayout::Flat, NorthEast) => (1, -1),
+            (HexLayout::Flat, North) => (0, -1),
+            (HexLayout::Flat, NorthWest) => (-1, 0),
+            (HexLayout::Flat, SouthWest) => (-1, 1),
+            (HexLayout::Flat, South) => (0, 1),
+            (HexLayout::Flat, SouthEast) => (1, 0),
+            _ => return None,
+        };
+        Some(Hex::new(dq, dr))
+    }
+}
+
+/// An unbounded hex grid that only stores occupied hexagons.
+#[derive(Debug, Clone)]
+pub struct HexMap<T> {
+    cells: HashMap<Hex, T>,
+}
+
+impl<T> Default for HexMap<T> {
+    fn default() -> Self {
+        HexMap {
+            cells: HashMap::new(),
+        }
+    }
+}
+
+impl<T> HexMap<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, hex: &Hex) -> Option<&T> {
+        self.cells.get(hex)
+    }
+
+    pub fn get_mut(&mut self, hex: &Hex) -> Option<&mut T> {
+        self.cells.get_mut(hex)
+    }
+
+    pub fn contains(&self, hex: &Hex) -> bool {
+        self.cells.contains_key(hex)
+    }
+
+    pub fn insert(&mut self, hex: Hex, value: T) -> Option<T> {
+        self.cells.insert(hex, value)
+    }
+
+    pub fn remove(&mut self, hex: &Hex) -> Option<T> {
+        self.cells.remove(hex)
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, Hex, T> {
+        self.cells.iter()
+    }
+
+    /// Occupied neighbors of `hex`, which itself doesn't need to be occupied.
+    pub fn neighbors(&self, hex: &Hex) -> impl Iterator<Item = (Hex, &T)> {
+        hex.neighbors()
+            .into_iter()
+            .filter_map(|n| Some((n, self.cells.get(&n)?)))
+    }
+
+    pub fn count_neighbors(&self, hex: &Hex, counted: impl Fn(&T) -> bool) -> usize {
+        self.neighbors(hex)
+            .filter(|(_, value)| counted(value))
+            .count()
+    }
+
+    /// Computes one generation of a cellular automaton over every occupied hexagon and its
+    /// neighbors, like [`super::Automaton`] does on a [`super::Map2D`].
+    ///
+    /// `rule` receives the current value (if any) and the number of neighbors matching
+    /// `counted`, and returns the next value (`None` leaves the hexagon empty).
+    pub fn step(
+        &self,
+        counted: impl Fn(&T) -> bool,
+        rule: impl Fn(Option<&T>, usize) -> Option<T>,
+    ) -> HexMap<T> {
+        let candidates: HashSet<Hex> = self
+            .cells
+            .keys()
+            .flat_map(|hex| hex.neighbors().into_iter().chain([*hex]))
+            .collect();
+
+        let cells = candidates
+            .into_iter()
+            .filter_map(|hex| {
+                let next = rule(self.cells.get(&hex), self.count_neighbors(&hex, &counted))?;
+                Some((hex, next))
+            })
+            .collect();
+        HexMap { cells }
+    }
+}
+
+impl<T> FromIterator<(Hex, T)> for HexMap<T> {
+    fn from_iter<I: IntoIterator<Item = (Hex, T)>>(iter: I) -> Self {
+        HexMap {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hex, HexLayout, HexMap};
+    use crate::utils::search::bfs;
+
+    #[test]
+    fn walks_flat_steps() {
+        let origin = Hex::default();
+        for (steps, dist) in [
+            ("ne,ne,ne", 3),
+            ("ne,ne,sw,sw", 0),
+            ("ne,ne,s,s", 2),
+            ("se,sw,se,sw,sw", 3),
+        ] {
+            let steps = HexLayout::Flat.parse_steps(steps).unwrap();
+            let end = origin.walk(&steps, HexLayout::Flat).unwrap();
+            assert_eq!(origin.dist(&end), dist);
+        }
+    }
+
+    #[test]
+    fn parses_pointy_steps() {
+        let steps = HexLayout::Pointy.parse_steps("nwwswee").unwrap();
+        assert_eq!(steps.len(), 5);
+        assert_eq!(
+            Hex::default().walk(&steps, HexLayout::Pointy),
+            Some(Hex::default())
+        );
+        assert!(HexLayout::Pointy.parse_steps("nn").is_err());
+    }
+
+    #[test]
+    fn separators_split_steps() {
+        use super::HexDirection::*;
+        assert_eq!(
+            HexLayout::Flat.parse_steps("ne, s\nnw"),
+            Ok(vec![NorthEast, South, NorthWest])
+        );
+        assert!(HexLayout::Flat.parse_steps("n,e").is_err());
+        assert!(HexLayout::Pointy.parse_steps("s,e").is_err());
+        assert!(HexLayout::Pointy.parse_steps("ne 1 ne").is_err());
+    }
+
+    #[test]
+    fn works_with_search_and_automaton() {
+        let result = bfs(
+            Hex::default(),
+            |hex| hex.neighbors(),
+            |&hex| hex == Hex::new(2, -3),
+        );
+        assert_eq!(result.cost(), Some(3));
+
+        // tiles with 1 or 2 neighbors survive, empty hexes next to exactly 2 tiles are born
+        let map: HexMap<()> = [(Hex::new(0, 0), ()), (Hex::new(1, 0), ())]
+            .into_iter()
+            .collect();
+        let next = map.step(
+            |_| true,
+            |current, n| match (current, n) {
+                (Some(_), 1 | 2) | (None, 2) => Some(()),
+                _ => None,
+            },
+        );
+        assert_eq!(next.len(), 4);
+    }
+}
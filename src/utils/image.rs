@@ -0,0 +1,315 @@
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    fs,
+    io::{Error, ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+use super::Map2D;
+
+pub type Rgb = [u8; 3];
+
+/// An RGB image, row by row, that can be encoded as PPM, PNG or (animated) GIF without
+/// any dependency.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Draws every tile of `map` as a `scale` x `scale` square of the colour given by `color`.
+    pub fn from_map<T>(map: &Map2D<T>, scale: usize, color: impl Fn(&T) -> Rgb) -> Image {
+        let (width, height) = (map.width * scale, map.height * scale);
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in map.tiles.chunks(map.width) {
+            let colors: Vec<Rgb> = row.iter().map(&color).collect();
+            for _ in 0..scale {
+                for &rgb in &colors {
+                    pixels.extend(std::iter::repeat_n(rgb, scale));
+                }
+            }
+        }
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Binary PPM (`P6`), the simplest format most viewers open.
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        out.extend(self.pixels.iter().flatten());
+        out
+    }
+
+    /// 8-bit RGB PNG. The image data is stored without compression, which keeps the encoder
+    /// tiny at the cost of bigger files.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)) {
+            // filter type: none
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // bit depth 8, colour type RGB, default compression, filtering and no interlacing
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut out, b"IHDR", &header);
+        png_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+        png_chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    /// Writes the image, picking the format from the extension (`ppm` or `png`).
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let bytes = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ppm") => self.to_ppm(),
+            Some("png") => self.to_png(),
+            _ => {
+                let msg = format!("unsupported image format: {}", path.display());
+                return Err(Error::new(ErrorKind::InvalidInput, msg));
+            }
+        };
+        fs::write(path, bytes)
+    }
+}
+
+/// Saves every frame as `<dir>/<prefix>_0000.<ext>`, `<dir>/<prefix>_0001.<ext>`, ...,
+/// creating `dir` if needed. Returns the paths of the written files.
+pub fn save_frames(
+    frames: &[Image],
+    dir: impl AsRef<Path>,
+    prefix: &str,
+    ext: &str,
+) -> Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    fs::create_dir_all(dir)?;
+    let digits = frames.len().saturating_sub(1).to_string().len().max(4);
+
+    frames
+        .iter()
+        .enumerate()
+        .map(|(i, frame)| {
+            let path = dir.join(format!("{prefix}_{i:0digits$}.{ext}"));
+            frame.save(&path)?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Encodes the frames as a looping animated GIF, showing each one for `delay` hundredths
+/// of a second.
+///
+/// All frames must have the same size and use at most 256 colours in total.
+pub fn encode_gif(frames: &[Image], delay: u16) -> Result<Vec<u8>> {
+    let invalid = |msg: &str| Error::new(ErrorKind::InvalidInput, msg.to_string());
+    let Some(first) = frames.first() else {
+        return Err(invalid("no frames to encode"));
+    };
+    if frames
+        .iter()
+        .any(|f| (f.width, f.height) != (first.width, first.height))
+    {
+        return Err(invalid("frames don't all have the same size"));
+    }
+
+    let mut palette: Vec<Rgb> = vec![];
+    let mut indices: HashMap<Rgb, u8> = HashMap::new();
+    for &rgb in frames.iter().flat_map(|f| &f.pixels) {
+        if let Entry::Vacant(entry) = indices.entry(rgb) {
+            if palette.len() == 256 {
+                return Err(invalid("GIF frames can't use more than 256 colours"));
+            }
+            entry.insert(palette.len() as u8);
+            palette.push(rgb);
+        }
+    }
+    // the colour table holds 2^(depth) entries, with at least 2 bits for the LZW codes
+    let depth = (palette.len().next_power_of_two().trailing_zeros() as u8).max(2);
+    palette.resize(1 << depth, [0, 0, 0]);
+
+    let (width, height) = (first.width as u16, first.height as u16);
+    let mut out = b"GIF89a".to_vec();
+    out.extend(width.to_le_bytes());
+    out.extend(height.to_le_bytes());
+    // global colour table, with 8 bits per primary colour
+    out.extend([0xf0 | (depth - 1), 0, 0]);
+    out.extend(palette.iter().flatten());
+    // loop forever
+    out.extend(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    for frame in frames {
+        out.extend([0x21, 0xf9, 0x04, 0x00]);
+        out.extend(delay.to_le_bytes());
+        out.extend([0x00, 0x00]);
+
+        out.push(0x2c);
+        out.extend([0, 0, 0, 0]);
+        out.extend(width.to_le_bytes());
+        out.extend(height.to_le_bytes());
+        out.push(0);
+
+        let pixels: Vec<u8> = frame.pixels.iter().map(|rgb| indices[rgb]).collect();
+        out.push(depth);
+        for block in lzw_encode(&pixels, depth).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend(block);
+        }
+        out.push(0);
+    }
+    out.push(0x3b);
+    Ok(out)
+}
+
+/// Saves the frames as an animated GIF, see [`encode_gif`].
+pub fn save_gif(frames: &[Image], delay: u16, path: impl AsRef<Path>) -> Result<()> {
+    fs::write(path, encode_gif(frames, delay)?)
+}
+
+fn png_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Wraps `data` in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        out.push(blocks.peek().is_none() as u8);
+        let len = block.len() as u16;
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    out.extend(((b << 16) | a).to_be_bytes());
+    out
+}
+
+/// Variable-width LZW, as used by GIF, packed least significant bit first.
+fn lzw_encode(pixels: &[u8], depth: u8) -> Vec<u8> {
+    let clear = 1u16 << depth;
+    let end = clear + 1;
+    let mut size = depth + 1;
+    let mut next = end + 1;
+    let mut codes: HashMap<(u16, u8), u16> = HashMap::new();
+
+    let mut out = vec![];
+    let (mut buffer, mut bits) = (0u32, 0u8);
+    let mut emit = |code: u16, size: u8| {
+        buffer |= (code as u32) << bits;
+        bits += size;
+        while bits >= 8 {
+            out.push(buffer as u8);
+            buffer >>= 8;
+            bits -= 8;
+        }
+    };
+
+    emit(clear, size);
+    let Some((&first, rest)) = pixels.split_first() else {
+        emit(end, size);
+        emit(0, 7);
+        return out;
+    };
+
+    let mut prefix = first as u16;
+    for &pixel in rest {
+        if let Some(&code) = codes.get(&(prefix, pixel)) {
+            prefix = code;
+            continue;
+        }
+
+        emit(prefix, size);
+        if next < 4096 {
+            if next == 1 << size {
+                size += 1;
+            }
+            codes.insert((prefix, pixel), next);
+            next += 1;
+        } else {
+            emit(clear, size);
+            codes.clear();
+            size = depth + 1;
+            next = end + 1;
+        }
+        prefix = pixel as u16;
+    }
+    emit(prefix, size);
+    emit(end, size);
+    // flush the last partial byte
+    emit(0, 7);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Image, crc32, encode_gif};
+    use crate::utils::Map2D;
+
+    fn image() -> Image {
+        let map = Map2D::<char>::parse("#.\n.#\n", &[]).unwrap();
+        Image::from_map(&map, 2, |&c| if c == '#' { [255, 0, 0] } else { [0; 3] })
+    }
+
+    #[test]
+    fn scales_tiles() {
+        let image = image();
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.pixels[5], [255, 0, 0]);
+        assert_eq!(image.pixels[6], [0, 0, 0]);
+        let ppm = image.to_ppm();
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+    }
+
+    #[test]
+    fn encodes_png_and_gif() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        let png = image().to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(png.ends_with(&[0xae, 0x42, 0x60, 0x82]));
+
+        let gif = encode_gif(&[image(), image()], 10).unwrap();
+        assert!(gif.starts_with(b"GIF89a\x04\x00\x04\x00"));
+        assert_eq!(gif.last(), Some(&0x3b));
+        assert!(encode_gif(&[], 10).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    ops::{Bound, RangeBounds, RangeInclusive},
+    str::FromStr,
+};
+
+use super::math::Integer;
+
+/// A set of integers stored as sorted, disjoint and non-adjacent inclusive ranges.
+///
+/// Touching or overlapping ranges are merged on insertion, so `1..=3` and `4..=5` become
+/// `1..=5`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+/// An error which can be returned when parsing an [`IntervalSet`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseIntervalError(pub String);
+
+impl Error for ParseIntervalError {}
+
+impl Display for ParseIntervalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid range: {}", self.0)
+    }
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        IntervalSet { ranges: vec![] }
+    }
+}
+
+impl<T: Integer> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inclusive bounds of `range`, `None` if it is empty.
+    fn bounds(range: impl RangeBounds<T>) -> Option<(T, T)> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) if start < T::MAX => start + T::ONE,
+            Bound::Excluded(_) => return None,
+            Bound::Unbounded => T::MIN,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end,
+            Bound::Excluded(&end) if end > T::MIN => end - T::ONE,
+            Bound::Excluded(_) => return None,
+            Bound::Unbounded => T::MAX,
+        };
+        (start <= end).then_some((start, end))
+    }
+
+    /// Adds every value of `range`, which can be inclusive (`a..=b`), exclusive (`a..b`)
+    /// or open-ended.
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        let Some((start, end)) = Self::bounds(range) else {
+            return;
+        };
+        // ranges ending before `start - 1` and starting after `end + 1` are untouched,
+        // the comparisons are ordered so that `+ 1` never overflows
+        let lo = self
+            .ranges
+            .partition_point(|&(_, b)| b < start && b + T::ONE < start);
+        let hi = self
+            .ranges
+            .partition_point(|&(a, _)| a <= end || a == end + T::ONE);
+
+        let merged = match self.ranges.get(lo..hi) {
+            Some([first, .., last]) | Some([first @ last]) => (first.0.min(start), last.1.max(end)),
+            _ => (start, end),
+        };
+        self.ranges.splice(lo..hi, [merged]);
+    }
+
+    /// Removes every value of `range`.
+    pub fn remove(&mut self, range: impl RangeBounds<T>) {
+        let Some((start, end)) = Self::bounds(range) else {
+            return;
+        };
+        let lo = self.ranges.partition_point(|&(_, b)| b < start);
+        let hi = self.ranges.partition_point(|&(a, _)| a <= end);
+        if lo >= hi {
+            return;
+        }
+
+        // the first and last overlapping ranges may stick out on either side
+        let (first, last) = (self.ranges[lo], self.ranges[hi - 1]);
+        let mut kept = vec![];
+        if first.0 < start {
+            kept.push((first.0, start - T::ONE));
+        }
+        if last.1 > end {
+            kept.push((end + T::ONE, last.1));
+        }
+        self.ranges.splice(lo..hi, kept);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let idx = self.ranges.partition_point(|&(_, b)| b < value);
+        self.ranges.get(idx).is_some_and(|&(a, _)| a <= value)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Number of disjoint ranges.
+    pub fn range_count(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Number of values in the set. Overflows if it doesn't fit in `T`.
+    pub fn total_len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::ZERO, |total, &(a, b)| total + (b - a) + T::ONE)
+    }
+
+    /// The disjoint ranges, in increasing order.
+    pub fn ranges(&self) -> impl ExactSizeIterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(a, b)| a..=b)
+    }
+
+    /// The missing ranges between the first and the last value of the set.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges
+            .windows(2)
+            .map(|pair| pair[0].1 + T::ONE..=pair[1].0 - T::ONE)
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut union = self.clone();
+        for &(a, b) in &other.ranges {
+            union.insert(a..=b);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+        while let (Some(&(a1, b1)), Some(&(a2, b2))) = (self.ranges.get(i), other.ranges.get(j)) {
+            let (start, end) = (a1.max(a2), b1.min(b2));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            // drop whichever range ends first, it can't overlap anything else
+            if b1 < b2 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { ranges }
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut difference = self.clone();
+        for &(a, b) in &other.ranges {
+            difference.remove(a..=b);
+        }
+        difference
+    }
+}
+
+impl<T: Integer, R: RangeBounds<T>> FromIterator<R> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+impl<T: Integer + FromStr> FromStr for IntervalSet<T> {
+    type Err = ParseIntervalError;
+
+    /// Parses inclusive `a-b` ranges (or single values) separated by commas or newlines.
+    /// Values may be negative, as in `-5--2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |value: &str, range: &str| {
+            value
+                .trim()
+                .parse::<T>()
+                .map_err(|_| ParseIntervalError(range.to_string()))
+        };
+
+        let mut set = IntervalSet::new();
+        for range in s
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+        {
+            // skip the first char, which may be the sign of the start
+            let dash = range.char_indices().skip(1).find(|&(_, c)| c == '-');
+            match dash.map(|(i, _)| range.split_at(i)) {
+                Some((start, end)) => set.insert(parse(start, range)?..=parse(&end[1..], range)?),
+                None => {
+                    let value = parse(range, range)?;
+                    set.insert(value..=value);
+                }
+            }
+        }
+        Ok(set)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::IntervalSet;
+
+    #[test]
+    fn merges_ranges() {
+        let mut set: IntervalSet<u64> = [3..=5, 10..=14, 16..=20, 12..=18].into_iter().collect();
+        assert_eq!(set.ranges().collect::<Vec<_>>(), [3..=5, 10..=20]);
+        set.insert(6..10);
+        assert_eq!(set.range_count(), 1);
+        set.insert(u64::MAX - 1..);
+        set.insert(..1);
+        assert_eq!(
+            set.ranges().collect::<Vec<_>>(),
+            [0..=0, 3..=20, u64::MAX - 1..=u64::MAX]
+        );
+        assert_eq!(set.gaps().collect::<Vec<_>>(), [1..=2, 21..=u64::MAX - 2]);
+        assert!(set.contains(20) && set.contains(u64::MAX) && !set.contains(21));
+    }
+
+    #[test]
+    fn combines_sets() {
+        let a: IntervalSet<i64> = "-10--5,0-10\n20-30".parse().unwrap();
+        let b: IntervalSet<i64> = "-6-2,8-25,42".parse().unwrap();
+        assert_eq!(a.total_len(), 6 + 11 + 11);
+
+        let ranges = |set: IntervalSet<i64>| set.ranges().collect::<Vec<_>>();
+        assert_eq!(ranges(a.union(&b)), [-10..=30, 42..=42]);
+        assert_eq!(
+            ranges(a.intersection(&b)),
+            [-6..=-5, 0..=2, 8..=10, 20..=25]
+        );
+        assert_eq!(ranges(a.difference(&b)), [-10..=-7, 3..=7, 26..=30]);
+        assert!("1-x".parse::<IntervalSet<i64>>().is_err());
+        assert!("é-1".parse::<IntervalSet<i64>>().is_err());
+    }
+
+    #[test]
+    fn parses_day_2_ranges() {
+        let set: IntervalSet<u64> = "11-22,95-115,998-1012\n".parse().unwrap();
+        assert_eq!(set.total_len(), 12 + 21 + 15);
+        assert!(set.contains(1000) && !set.contains(23));
+    }
+}
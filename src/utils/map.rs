@@ -1,10 +1,13 @@
 use std::{
     cmp::Ordering,
-    collections::{BinaryHeap, HashMap},
+    collections::{BinaryHeap, HashMap, HashSet},
+    error::Error,
     fmt::Display,
+    hash::{Hash, Hasher},
+    str::FromStr,
 };
 
-use super::{Direction, Pos2D};
+use super::{Direction, Overlay, Pos2D, position::neighbor_offsets};
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct Node {
@@ -14,21 +17,157 @@ pub struct Node {
 
 type Tile = usize;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Map2D<T> {
     pub width: usize,
     pub height: usize,
     pub tiles: Vec<T>,
-    pub start: Tile,
-    pub end: Tile,
+    pub markers: HashMap<char, Vec<Tile>>,
+    /// When set, the map is toroidal: positions past an edge wrap around to the other side.
+    pub wrapping: bool,
+}
+
+/// An error which can be returned when parsing a [`Map2D`] from text.
+#[derive(Debug)]
+pub enum Map2DError {
+    Empty,
+    NotRectangular {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    InvalidTile {
+        c: char,
+        position: Pos2D,
+    },
+}
+
+impl Error for Map2DError {}
+
+impl Display for Map2DError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Map2DError::Empty => f.write_str("map input is empty"),
+            Map2DError::NotRectangular {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line} has {found} tiles, expected {expected} like the first line"
+            ),
+            Map2DError::InvalidTile { c, position } => write!(
+                f,
+                "invalid tile '{c}' at (x: {}, y: {})",
+                position.x, position.y
+            ),
+        }
+    }
 }
 
 impl<T> Map2D<T>
 where
-    T: Display + PartialEq,
+    T: TryFrom<char>,
 {
+    /// Parses a rectangular grid, mapping every char to a tile with `T::try_from`.
+    ///
+    /// The index of every char listed in `markers` is recorded in [`Map2D::markers`].
+    /// Marker chars are still handed to `T::try_from`, so the tile type decides what
+    /// lies underneath them (e.g. `'S'` mapping to an empty floor tile).
+    pub fn parse(input: &str, markers: &[char]) -> Result<Self, Map2DError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut tiles = vec![];
+        let mut found_markers: HashMap<char, Vec<Tile>> = HashMap::new();
+
+        for (y, line) in input.trim_end_matches(['\n', '\r']).lines().enumerate() {
+            let row_start = tiles.len();
+
+            for (x, c) in line.chars().enumerate() {
+                if markers.contains(&c) {
+                    found_markers.entry(c).or_default().push(tiles.len());
+                }
+
+                let position = Pos2D::new(x as i32, y as i32);
+                let tile = T::try_from(c).map_err(|_| Map2DError::InvalidTile { c, position })?;
+                tiles.push(tile);
+            }
+
+            let row_width = tiles.len() - row_start;
+            let expected = *width.get_or_insert(row_width);
+            if row_width != expected {
+                return Err(Map2DError::NotRectangular {
+                    line: y,
+                    expected,
+                    found: row_width,
+                });
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Map2D {
+                width,
+                height,
+                tiles,
+                markers: found_markers,
+                wrapping: false,
+            }),
+            _ => Err(Map2DError::Empty),
+        }
+    }
+}
+
+/// Hashes everything but the markers (a `HashMap`), which is still consistent with `Eq`.
+impl<T: Hash> Hash for Map2D<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.height.hash(state);
+        self.tiles.hash(state);
+        self.wrapping.hash(state);
+    }
+}
+
+impl<T> FromStr for Map2D<T>
+where
+    T: TryFrom<char>,
+{
+    type Err = Map2DError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Map2D::parse(s, &[])
+    }
+}
+
+impl<T> Map2D<T> {
+    /// Switches the map to (or out of) toroidal mode, see [`Map2D::wrapping`].
+    pub fn with_wrapping(mut self, wrapping: bool) -> Self {
+        self.wrapping = wrapping;
+        self
+    }
+
+    /// Brings `position` back onto the map, wrapping around the edges in toroidal mode.
+    ///
+    /// Returns `None` for out of bounds positions when the map doesn't wrap.
+    pub fn normalize(&self, position: &Pos2D) -> Option<Pos2D> {
+        if self.wrapping {
+            return Some(Pos2D::new(
+                position.x.rem_euclid(self.width as i32),
+                position.y.rem_euclid(self.height as i32),
+            ));
+        }
+
+        self.is_valid_pos(position).then_some(*position)
+    }
+
     pub fn pos2idx(&self, position: &Pos2D) -> Tile {
-        position.to_idx(self.width, self.height).unwrap()
+        self.try_pos2idx(position).unwrap()
+    }
+
+    pub fn try_pos2idx(&self, position: &Pos2D) -> Option<Tile> {
+        self.normalize(position)?
+            .to_idx(self.width, self.height)
+            .ok()
     }
 
     pub fn idx2pos(&self, idx: usize) -> Pos2D {
@@ -41,46 +180,110 @@ where
         (a_pos.dist(&b_pos) * 10.) as i32
     }
 
-    pub fn get_neighbors(&self, node: Tile, neighbor_type: T, include_corners: bool) -> Vec<Tile> {
-        self.idx2pos(node)
-            .neighbors(include_corners)
-            .iter()
-            .filter(|pos| self.is_valid_pos(&pos))
-            .map(|pos| self.pos2idx(pos))
-            .filter(|&node| self.tiles[node] == neighbor_type)
-            .collect()
+    /// Shortest `(dx, dy)` step from `a` to `b`, going across the edges in toroidal mode.
+    pub fn delta(&self, a: &Pos2D, b: &Pos2D) -> (i32, i32) {
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        if !self.wrapping {
+            return (dx, dy);
+        }
+
+        let shortest = |d: i32, len: i32| {
+            let d = d.rem_euclid(len);
+            if d > len / 2 { d - len } else { d }
+        };
+        (
+            shortest(dx, self.width as i32),
+            shortest(dy, self.height as i32),
+        )
     }
 
     pub fn is_valid_pos(&self, position: &Pos2D) -> bool {
-        position.x >= 0
-            && position.x < (self.width as i32)
-            && position.y >= 0
-            && position.y < (self.height as i32)
+        self.wrapping
+            || position.x >= 0
+                && position.x < (self.width as i32)
+                && position.y >= 0
+                && position.y < (self.height as i32)
     }
 
-    pub fn display(&self) {
-        for (idx, tile) in self.tiles.iter().enumerate() {
-            if idx % self.width == 0 {
-                println!("");
+    /// Indices of the in-bounds neighbors of tile `idx`, in the order of [`Pos2D::neighbors`].
+    ///
+    /// Works on tile indices directly, without going through [`Pos2D`] or allocating. On a
+    /// wrapping map only 1 or 2 tiles wide or high, several offsets land on the same tile:
+    /// it is only returned once, and never when it is `idx` itself.
+    pub fn neighbor_indices(
+        &self,
+        idx: Tile,
+        include_corners: bool,
+    ) -> impl Iterator<Item = Tile> + use<T> {
+        let offsets = neighbor_offsets(include_corners);
+        let (width, height, wrapping) = (self.width as i32, self.height as i32, self.wrapping);
+        let (x, y) = ((idx % self.width) as i32, (idx / self.width) as i32);
+        // offsets can only collide when wrapping around a side at most 2 tiles long
+        let tiny_torus = wrapping && (width <= 2 || height <= 2);
+
+        let neighbor = move |(dx, dy): (i32, i32)| {
+            let (mut nx, mut ny) = (x + dx, y + dy);
+            if wrapping {
+                nx = nx.rem_euclid(width);
+                ny = ny.rem_euclid(height);
+            } else if nx < 0 || nx >= width || ny < 0 || ny >= height {
+                return None;
             }
-            print!("{}", tile);
-        }
-        println!("\n");
+            Some(ny as usize * width as usize + nx as usize)
+        };
+
+        offsets.iter().enumerate().filter_map(move |(i, &offset)| {
+            let neighbor_idx = neighbor(offset)?;
+            let duplicate = tiny_torus
+                && (neighbor_idx == idx
+                    || offsets[..i]
+                        .iter()
+                        .any(|&previous| neighbor(previous) == Some(neighbor_idx)));
+            (!duplicate).then_some(neighbor_idx)
+        })
     }
 
-    pub fn display_path(&self, path: &Vec<Tile>) {
-        for (idx, tile) in self.tiles.iter().enumerate() {
-            if idx % self.width == 0 {
-                println!("");
-            }
+    /// Number of neighbors of tile `idx` matching `predicate`, without allocating.
+    pub fn count_neighbors(
+        &self,
+        idx: Tile,
+        include_corners: bool,
+        predicate: impl Fn(&T) -> bool,
+    ) -> usize {
+        self.neighbor_indices(idx, include_corners)
+            .filter(|&neighbor| predicate(&self.tiles[neighbor]))
+            .count()
+    }
 
-            if path.contains(&idx) {
-                print!("@");
-            } else {
-                print!("{}", tile);
-            }
-        }
-        println!("\n");
+    /// Returns the first tile marked with `marker`, if any.
+    pub fn marker(&self, marker: char) -> Option<Tile> {
+        self.markers.get(&marker)?.first().copied()
+    }
+
+    /// Returns every tile marked with `marker`.
+    pub fn marker_all(&self, marker: char) -> &[Tile] {
+        self.markers.get(&marker).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl<T> Map2D<T>
+where
+    T: Display + PartialEq,
+{
+    pub fn get_neighbors(&self, node: Tile, neighbor_type: T, include_corners: bool) -> Vec<Tile> {
+        self.neighbor_indices(node, include_corners)
+            .filter(|&node| self.tiles[node] == neighbor_type)
+            .collect()
+    }
+
+    pub fn display(&self) {
+        self.renderer().print();
+    }
+
+    /// Prints the map with every tile of `path` drawn as `@`, see [`Map2D::renderer`] for
+    /// more options.
+    pub fn display_path(&self, path: &[Tile]) {
+        self.renderer().overlay(Overlay::path("path", path)).print();
     }
 }
 
@@ -102,6 +305,43 @@ impl PartialOrd for State {
     }
 }
 
+/// Target of an [`AStar`] search.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Goal {
+    /// Reach this exact position, facing this exact direction.
+    Node(Node),
+    /// Reach this position, facing any direction.
+    Position(Pos2D),
+}
+
+impl Goal {
+    pub fn position(&self) -> Pos2D {
+        match self {
+            Goal::Node(node) => node.position,
+            Goal::Position(position) => *position,
+        }
+    }
+
+    pub fn matches(&self, node: &Node) -> bool {
+        match self {
+            Goal::Node(goal) => goal == node,
+            Goal::Position(position) => node.position == *position,
+        }
+    }
+}
+
+impl From<Node> for Goal {
+    fn from(value: Node) -> Self {
+        Goal::Node(value)
+    }
+}
+
+impl From<Pos2D> for Goal {
+    fn from(value: Pos2D) -> Self {
+        Goal::Position(value)
+    }
+}
+
 pub struct AStar {
     turn_penalty: i32,
 }
@@ -111,54 +351,60 @@ impl AStar {
         AStar { turn_penalty }
     }
 
-    fn heuristic(&self, node: &Node, goal: &Node) -> i32 {
-        let manhattan_distance =
-            (node.position.x - goal.position.x).abs() + (node.position.y - goal.position.y).abs();
-        let min_turns = self.estimate_min_turns(node, goal);
+    fn heuristic<T>(&self, map: &Map2D<T>, node: &Node, goal: Pos2D) -> i32 {
+        let (dx, dy) = map.delta(&node.position, &goal);
+        let manhattan_distance = dx.abs() + dy.abs();
+        if manhattan_distance == 0 {
+            return 0;
+        }
+        // on a wrapping map the goal can also be reached the long way round without turning,
+        // so only the distance is a safe lower bound
+        if map.wrapping {
+            return manhattan_distance;
+        }
+        let min_turns = self.estimate_min_turns(node, (dx, dy));
         manhattan_distance + min_turns * self.turn_penalty
     }
 
-    fn estimate_min_turns(&self, node: &Node, goal: &Node) -> i32 {
+    fn estimate_min_turns(&self, node: &Node, delta: (i32, i32)) -> i32 {
         // Simplified estimation: 0 if same direction, 1 otherwise
-        if node.direction == self.get_direction_to_goal(node, goal) {
+        if node.direction == self.get_direction_to_goal(delta) {
             0
         } else {
             1
         }
     }
 
-    fn get_direction_to_goal(&self, node: &Node, goal: &Node) -> Direction {
-        if (goal.position.x - node.position.x).abs() > (goal.position.y - node.position.y).abs() {
-            if goal.position.x > node.position.x {
+    fn get_direction_to_goal(&self, (dx, dy): (i32, i32)) -> Direction {
+        if dx.abs() > dy.abs() {
+            if dx > 0 {
                 Direction::East
             } else {
                 Direction::West
             }
+        } else if dy < 0 {
+            Direction::North
         } else {
-            if goal.position.y < node.position.y {
-                Direction::North
-            } else {
-                Direction::South
-            }
+            Direction::South
         }
     }
 
-    fn get_neighbors<T: Clone + PartialEq>(
+    fn get_neighbors<T>(
         &self,
         og_map: &Map2D<T>,
         node: &Node,
-        neighbor_type: &T,
+        is_passable: &impl Fn(&T) -> bool,
     ) -> Vec<Node> {
         let mut actual_neighbors = vec![];
 
-        let neighbors = node.position.neighbors(false);
-        for neighbor_pos in neighbors {
-            let map_idx = neighbor_pos.to_idx(og_map.width, og_map.height).unwrap();
-            let tile = og_map.tiles[map_idx].clone();
-            if tile == *neighbor_type {
-                let direction = node.position.get_direction(neighbor_pos);
+        for direction in Direction::ALL {
+            // positions outside of a non-wrapping map are simply not reachable
+            let Some(map_idx) = og_map.try_pos2idx(&(node.position + direction)) else {
+                continue;
+            };
+            if is_passable(&og_map.tiles[map_idx]) {
                 actual_neighbors.push(Node {
-                    position: neighbor_pos,
+                    position: og_map.idx2pos(map_idx),
                     direction,
                 });
             }
@@ -176,13 +422,28 @@ impl AStar {
         }
     }
 
-    pub fn find_path<T: Clone + PartialEq>(
+    /// Finds the cheapest path from `start` to `goal`, only walking on `walkable` tiles.
+    pub fn find_path<T: PartialEq>(
+        &self,
+        start: Node,
+        goal: impl Into<Goal>,
+        map: &Map2D<T>,
+        walkable: &[T],
+    ) -> Option<Vec<Node>> {
+        self.find_path_by(start, goal, map, |tile| walkable.contains(tile))
+    }
+
+    /// Finds the cheapest path from `start` to `goal`, only walking on tiles for which
+    /// `is_passable` returns `true`.
+    pub fn find_path_by<T>(
         &self,
         start: Node,
-        goal: Node,
+        goal: impl Into<Goal>,
         map: &Map2D<T>,
-        neighbor_type: &T,
+        is_passable: impl Fn(&T) -> bool,
     ) -> Option<Vec<Node>> {
+        let goal = goal.into();
+        let goal_pos = goal.position();
         let mut open_set = BinaryHeap::new();
         let mut came_from = HashMap::new();
         let mut g_score = HashMap::new();
@@ -194,18 +455,18 @@ impl AStar {
         g_score.insert(start.clone(), 0);
 
         while let Some(State { node: current, .. }) = open_set.pop() {
-            if current == goal {
+            if goal.matches(&current) {
                 return Some(self.reconstruct_path(came_from, current));
             }
 
-            for neighbor in self.get_neighbors(&map, &current, neighbor_type) {
+            for neighbor in self.get_neighbors(map, &current, &is_passable) {
                 let tentative_g_score =
                     g_score[&current] + self.calculate_cost(&current, &neighbor);
 
                 if tentative_g_score < *g_score.get(&neighbor).unwrap_or(&i32::MAX) {
                     came_from.insert(neighbor.clone(), current.clone());
                     g_score.insert(neighbor.clone(), tentative_g_score);
-                    let f_score = tentative_g_score + self.heuristic(&neighbor, &goal);
+                    let f_score = tentative_g_score + self.heuristic(map, &neighbor, goal_pos);
                     open_set.push(State {
                         cost: f_score,
                         node: neighbor,
@@ -217,6 +478,86 @@ impl AStar {
         None
     }
 
+    /// Finds every cheapest path from `start` to `goal`, only walking on `walkable` tiles.
+    pub fn find_all_paths<T: PartialEq>(
+        &self,
+        start: Node,
+        goal: impl Into<Goal>,
+        map: &Map2D<T>,
+        walkable: &[T],
+    ) -> Option<OptimalPaths> {
+        self.find_all_paths_by(start, goal, map, |tile| walkable.contains(tile))
+    }
+
+    /// Finds every cheapest path from `start` to `goal`, only walking on tiles for which
+    /// `is_passable` returns `true`.
+    ///
+    /// Unlike [`AStar::find_path_by`], this runs without the heuristic so that every
+    /// equal-cost predecessor is guaranteed to be recorded.
+    pub fn find_all_paths_by<T>(
+        &self,
+        start: Node,
+        goal: impl Into<Goal>,
+        map: &Map2D<T>,
+        is_passable: impl Fn(&T) -> bool,
+    ) -> Option<OptimalPaths> {
+        let goal = goal.into();
+        let mut open_set = BinaryHeap::new();
+        let mut came_from: HashMap<Node, Vec<Node>> = HashMap::new();
+        let mut g_score = HashMap::new();
+        let mut best: Option<(i32, Vec<Node>)> = None;
+
+        open_set.push(State {
+            cost: 0,
+            node: start.clone(),
+        });
+        g_score.insert(start.clone(), 0);
+
+        while let Some(State {
+            cost,
+            node: current,
+        }) = open_set.pop()
+        {
+            if cost > g_score[&current] {
+                continue;
+            }
+            if let Some((best_cost, _)) = &best
+                && cost > *best_cost
+            {
+                break;
+            }
+
+            if goal.matches(&current) {
+                best.get_or_insert_with(|| (cost, vec![])).1.push(current);
+                continue;
+            }
+
+            for neighbor in self.get_neighbors(map, &current, &is_passable) {
+                let tentative_g_score = cost + self.calculate_cost(&current, &neighbor);
+                let neighbor_g_score = *g_score.get(&neighbor).unwrap_or(&i32::MAX);
+
+                if tentative_g_score < neighbor_g_score {
+                    came_from.insert(neighbor.clone(), vec![current.clone()]);
+                    g_score.insert(neighbor.clone(), tentative_g_score);
+                    open_set.push(State {
+                        cost: tentative_g_score,
+                        node: neighbor,
+                    });
+                } else if tentative_g_score == neighbor_g_score {
+                    came_from.entry(neighbor).or_default().push(current.clone());
+                }
+            }
+        }
+
+        let (cost, goals) = best?;
+        Some(OptimalPaths {
+            cost,
+            goals,
+            came_from,
+            g_score,
+        })
+    }
+
     fn reconstruct_path(&self, came_from: HashMap<Node, Node>, current: Node) -> Vec<Node> {
         let mut path = vec![current.clone()];
         let mut current = current;
@@ -228,3 +569,293 @@ impl AStar {
         path
     }
 }
+
+/// Every cheapest path found by [`AStar::find_all_paths_by`], stored as a graph of
+/// equal-cost predecessors.
+#[derive(Debug)]
+pub struct OptimalPaths {
+    pub cost: i32,
+    /// Goal nodes reached at the optimal cost (several for a [`Goal::Position`]).
+    pub goals: Vec<Node>,
+    came_from: HashMap<Node, Vec<Node>>,
+    g_score: HashMap<Node, i32>,
+}
+
+impl OptimalPaths {
+    fn predecessors(&self, node: &Node) -> &[Node] {
+        self.came_from.get(node).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every node that lies on at least one optimal path.
+    pub fn nodes(&self) -> HashSet<Node> {
+        let mut seen: HashSet<Node> = self.goals.iter().cloned().collect();
+        let mut stack = self.goals.clone();
+        while let Some(node) = stack.pop() {
+            for prev in self.predecessors(&node) {
+                if seen.insert(prev.clone()) {
+                    stack.push(prev.clone());
+                }
+            }
+        }
+        seen
+    }
+
+    /// Every position that lies on at least one optimal path, regardless of direction.
+    pub fn positions(&self) -> HashSet<Pos2D> {
+        self.nodes().into_iter().map(|node| node.position).collect()
+    }
+
+    /// Number of distinct optimal paths.
+    pub fn count(&self) -> u64 {
+        let mut nodes: Vec<Node> = self.nodes().into_iter().collect();
+        nodes.sort_by_key(|node| self.g_score[node]);
+
+        // predecessors always have a strictly lower g score, so a single pass suffices
+        let mut counts: HashMap<&Node, u64> = HashMap::new();
+        for node in &nodes {
+            let preds = self.predecessors(node);
+            let count = if preds.is_empty() {
+                1
+            } else {
+                preds.iter().map(|prev| counts[prev]).sum()
+            };
+            counts.insert(node, count);
+        }
+
+        self.goals.iter().map(|goal| counts[goal]).sum()
+    }
+
+    /// Iterates over every optimal path, from start to goal.
+    pub fn iter(&self) -> OptimalPathsIter<'_> {
+        OptimalPathsIter {
+            paths: self,
+            stack: self.goals.iter().map(|goal| vec![goal.clone()]).collect(),
+        }
+    }
+}
+
+pub struct OptimalPathsIter<'a> {
+    paths: &'a OptimalPaths,
+    // partial paths, stored backwards from a goal
+    stack: Vec<Vec<Node>>,
+}
+
+impl Iterator for OptimalPathsIter<'_> {
+    type Item = Vec<Node>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(mut path) = self.stack.pop() {
+            let preds = self.paths.predecessors(path.last().unwrap());
+            let Some((first, rest)) = preds.split_first() else {
+                path.reverse();
+                return Some(path);
+            };
+
+            for prev in rest {
+                let mut branch = path.clone();
+                branch.push(prev.clone());
+                self.stack.push(branch);
+            }
+            path.push(first.clone());
+            self.stack.push(path);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AStar, Goal, Map2D, Map2DError, Node};
+    use crate::utils::{Direction, Pos2D};
+
+    #[test]
+    fn parses_markers() {
+        let map = Map2D::<char>::parse("#S.\n..E\n", &['S', 'E']).unwrap();
+        assert_eq!((map.width, map.height), (3, 2));
+        assert_eq!(map.marker('S'), Some(1));
+        assert_eq!(map.marker('E'), Some(5));
+        assert_eq!(map.marker('X'), None);
+    }
+
+    #[test]
+    fn rejects_ragged_rows() {
+        let err = "...\n..\n".parse::<Map2D<char>>().unwrap_err();
+        assert!(matches!(
+            err,
+            Map2DError::NotRectangular {
+                line: 1,
+                expected: 3,
+                found: 2
+            }
+        ));
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Wall;
+
+    impl TryFrom<char> for Wall {
+        type Error = ();
+
+        fn try_from(value: char) -> Result<Self, Self::Error> {
+            if value == '#' { Ok(Wall) } else { Err(()) }
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_tiles() {
+        let err = "##\n#x\n".parse::<Map2D<Wall>>().unwrap_err();
+        assert!(matches!(err, Map2DError::InvalidTile { c: 'x', .. }));
+    }
+
+    #[test]
+    fn astar_walks_along_borders() {
+        // no wall ring: the path has to hug the top and right edges
+        let map = Map2D::<char>::parse("S..\n##.\n..E\n", &['S', 'E']).unwrap();
+        let start = Node {
+            position: map.idx2pos(map.marker('S').unwrap()),
+            direction: Direction::East,
+        };
+        let goal = map.idx2pos(map.marker('E').unwrap());
+
+        let path = AStar::new(1000)
+            .find_path(start, goal, &map, &['.', 'E'])
+            .unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.last().unwrap().position, Pos2D::new(2, 2));
+        assert_eq!(path.last().unwrap().direction, Direction::South);
+    }
+
+    #[test]
+    fn astar_goal_node_requires_direction() {
+        let map = Map2D::<char>::parse("...\n", &[]).unwrap();
+        let start = Node {
+            position: Pos2D::new(0, 0),
+            direction: Direction::East,
+        };
+        let goal = Node {
+            position: Pos2D::new(2, 0),
+            direction: Direction::West,
+        };
+
+        let astar = AStar::new(0);
+        assert!(
+            astar
+                .find_path_by(start.clone(), goal, &map, |_| true)
+                .is_none()
+        );
+        assert!(
+            astar
+                .find_path_by(start, Goal::Position(Pos2D::new(2, 0)), &map, |_| true)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn astar_counts_all_optimal_paths() {
+        // two equally cheap routes around the central wall
+        let map = Map2D::<char>::parse("...\n.#.\n...\n", &[]).unwrap();
+        let start = Node {
+            position: Pos2D::new(0, 0),
+            direction: Direction::East,
+        };
+        let goal = Goal::Position(Pos2D::new(2, 2));
+
+        let paths = AStar::new(0)
+            .find_all_paths(start.clone(), goal.clone(), &map, &['.'])
+            .unwrap();
+        assert_eq!(paths.cost, 4);
+        assert_eq!(paths.count(), 2);
+        assert_eq!(paths.iter().count(), 2);
+        assert_eq!(paths.positions().len(), 8);
+
+        // going south first needs one more turn
+        let paths = AStar::new(1000)
+            .find_all_paths(start, goal, &map, &['.'])
+            .unwrap();
+        assert_eq!(paths.cost, 1004);
+        assert_eq!(paths.count(), 1);
+        assert_eq!(paths.positions().len(), 5);
+    }
+
+    #[test]
+    fn wrapping_maps_normalize_positions() {
+        let map = Map2D::<char>::parse("ab\ncd\n", &[])
+            .unwrap()
+            .with_wrapping(true);
+        assert!(map.is_valid_pos(&Pos2D::new(-1, 5)));
+        assert_eq!(map.pos2idx(&Pos2D::new(-1, 5)), 3);
+        assert_eq!(map.delta(&Pos2D::new(0, 0), &Pos2D::new(1, 1)), (1, 1));
+        assert_eq!(map.get_neighbors(0, 'c', false), vec![2]);
+        assert_eq!(
+            map.neighbor_indices(0, true).collect::<Vec<_>>(),
+            vec![3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn astar_crosses_wrapping_edges() {
+        let map = Map2D::<char>::parse(".#...\n", &[]).unwrap();
+        let start = Node {
+            position: Pos2D::new(0, 0),
+            direction: Direction::West,
+        };
+        let goal = Goal::Position(Pos2D::new(3, 0));
+
+        let astar = AStar::new(0);
+        assert_eq!(
+            astar.find_path(start.clone(), goal.clone(), &map, &['.']),
+            None
+        );
+
+        let path = astar
+            .find_path(start, goal, &map.with_wrapping(true), &['.'])
+            .unwrap();
+        let xs: Vec<_> = path.iter().map(|node| node.position.x).collect();
+        assert_eq!(xs, vec![0, 4, 3]);
+    }
+
+    #[test]
+    fn astar_finds_cheapest_path_around_wrapping_edges() {
+        // facing away from the goal: going all the way round is cheaper than turning
+        let map = Map2D::<char>::parse("....\n", &[])
+            .unwrap()
+            .with_wrapping(true);
+        let start = Node {
+            position: Pos2D::new(0, 0),
+            direction: Direction::West,
+        };
+        let goal = Goal::Position(Pos2D::new(1, 0));
+
+        let astar = AStar::new(1000);
+        let path = astar
+            .find_path(start.clone(), goal.clone(), &map, &['.'])
+            .unwrap();
+        let xs: Vec<_> = path.iter().map(|node| node.position.x).collect();
+        assert_eq!(xs, vec![0, 3, 2, 1]);
+        assert_eq!(
+            astar
+                .find_all_paths(start, goal, &map, &['.'])
+                .unwrap()
+                .cost,
+            3
+        );
+    }
+
+    #[test]
+    fn neighbor_indices_match_positions() {
+        let map = Map2D::<char>::parse("abc\ndef\nghi\n", &[]).unwrap();
+        for idx in 0..map.tiles.len() {
+            for include_corners in [false, true] {
+                let expected: Vec<_> = map
+                    .idx2pos(idx)
+                    .neighbors(include_corners)
+                    .iter()
+                    .filter_map(|pos| map.try_pos2idx(pos))
+                    .collect();
+                let actual: Vec<_> = map.neighbor_indices(idx, include_corners).collect();
+                assert_eq!(actual, expected);
+            }
+        }
+        assert_eq!(map.count_neighbors(4, true, |&c| c < 'e'), 4);
+    }
+}
@@ -0,0 +1,342 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{
+    Pos3D,
+    position3d::{NEIGHBORS_6, NEIGHBORS_26},
+    region::flood_fill,
+};
+
+type Voxel = usize;
+
+/// A dense 3D grid, stored layer by layer (`z`), then row by row (`y`).
+#[derive(Debug, Clone)]
+pub struct Map3D<T> {
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+    pub tiles: Vec<T>,
+}
+
+impl<T> Map3D<T>
+where
+    T: Clone,
+{
+    pub fn new(width: usize, height: usize, depth: usize, fill: T) -> Self {
+        Map3D {
+            width,
+            height,
+            depth,
+            tiles: vec![fill; width * height * depth],
+        }
+    }
+
+    /// Builds the smallest map holding every point, with `filled` voxels at the points and
+    /// `empty` ones everywhere else. Points must have non-negative coordinates.
+    pub fn from_points(points: &[Pos3D], empty: T, filled: T) -> Self {
+        let size = |coord: fn(&Pos3D) -> i32| {
+            points
+                .iter()
+                .map(coord)
+                .max()
+                .map_or(0, |max| max as usize + 1)
+        };
+        let mut map = Map3D::new(size(|p| p.x), size(|p| p.y), size(|p| p.z), empty);
+        for point in points {
+            let idx = map.pos2idx(point);
+            map.tiles[idx] = filled.clone();
+        }
+        map
+    }
+}
+
+impl<T> Map3D<T> {
+    pub fn pos2idx(&self, position: &Pos3D) -> Voxel {
+        self.try_pos2idx(position).unwrap()
+    }
+
+    pub fn try_pos2idx(&self, position: &Pos3D) -> Option<Voxel> {
+        position.to_idx(self.width, self.height, self.depth).ok()
+    }
+
+    pub fn idx2pos(&self, idx: Voxel) -> Pos3D {
+        Pos3D::from_idx(idx, self.width, self.height, self.depth).unwrap()
+    }
+
+    pub fn is_valid_pos(&self, position: &Pos3D) -> bool {
+        self.try_pos2idx(position).is_some()
+    }
+
+    pub fn get(&self, position: &Pos3D) -> Option<&T> {
+        Some(&self.tiles[self.try_pos2idx(position)?])
+    }
+
+    pub fn get_mut(&mut self, position: &Pos3D) -> Option<&mut T> {
+        let idx = self.try_pos2idx(position)?;
+        Some(&mut self.tiles[idx])
+    }
+
+    /// Indices of the in-bounds neighbors of voxel `idx` (6, or 26 with corners), in the
+    /// order of [`super::Point3D::neighbors`].
+    ///
+    /// Works on voxel indices directly, without going through [`Pos3D`] or allocating.
+    pub fn neighbor_indices(
+        &self,
+        idx: Voxel,
+        include_corners: bool,
+    ) -> impl Iterator<Item = Voxel> + use<T> {
+        let offsets: &'static [(i32, i32, i32)] = if include_corners {
+            &NEIGHBORS_26
+        } else {
+            &NEIGHBORS_6
+        };
+        let (width, height, depth) = (self.width as i32, self.height as i32, self.depth as i32);
+        let (x, y, z) = (
+            (idx % self.width) as i32,
+            (idx / self.width % self.height) as i32,
+            (idx / (self.width * self.height)) as i32,
+        );
+
+        offsets.iter().filter_map(move |&(dx, dy, dz)| {
+            let (nx, ny, nz) = (x + dx, y + dy, z + dz);
+            let inside =
+                (0..width).contains(&nx) && (0..height).contains(&ny) && (0..depth).contains(&nz);
+            inside.then(|| ((nz * height + ny) * width + nx) as usize)
+        })
+    }
+
+    /// Returns every voxel connected to `start` through voxels matching `is_inside`, `start`
+    /// included even if it doesn't match. Same as [`Map2D::flood_fill_by`] in 3D.
+    ///
+    /// [`Map2D::flood_fill_by`]: super::Map2D::flood_fill_by
+    pub fn flood_fill_by(
+        &self,
+        start: Voxel,
+        include_corners: bool,
+        is_inside: impl Fn(&T) -> bool,
+    ) -> Vec<Voxel> {
+        flood_fill(
+            self.tiles.len(),
+            start,
+            |idx| self.neighbor_indices(idx, include_corners),
+            |idx| is_inside(&self.tiles[idx]),
+        )
+    }
+
+    /// Number of faces of solid voxels that don't touch another solid voxel, including
+    /// the faces of inner air pockets.
+    pub fn surface_area(&self, is_solid: impl Fn(&T) -> bool) -> usize {
+        self.count_faces(&is_solid, |idx| !is_solid(&self.tiles[idx]))
+    }
+
+    /// Number of faces of solid voxels that can be reached from outside of the map,
+    /// ignoring fully enclosed air pockets.
+    pub fn exterior_surface_area(&self, is_solid: impl Fn(&T) -> bool) -> usize {
+        // every air voxel reachable from the map's boundary is exterior air
+        let mut exterior = vec![false; self.tiles.len()];
+        let mut queue: VecDeque<Voxel> = (0..self.tiles.len())
+            .filter(|&idx| !is_solid(&self.tiles[idx]) && self.is_boundary(idx))
+            .collect();
+        for &idx in &queue {
+            exterior[idx] = true;
+        }
+
+        while let Some(current) = queue.pop_front() {
+            for idx in self.neighbor_indices(current, false) {
+                if !exterior[idx] && !is_solid(&self.tiles[idx]) {
+                    exterior[idx] = true;
+                    queue.push_back(idx);
+                }
+            }
+        }
+
+        self.count_faces(&is_solid, |idx| exterior[idx])
+    }
+
+    fn is_boundary(&self, idx: Voxel) -> bool {
+        let pos = self.idx2pos(idx);
+        let on_edge = |coord: i32, size: usize| coord == 0 || coord as usize == size - 1;
+        on_edge(pos.x, self.width) || on_edge(pos.y, self.height) || on_edge(pos.z, self.depth)
+    }
+
+    /// Counts the faces of solid voxels that lie on the map's border or against a voxel
+    /// matching `is_open`.
+    fn count_faces(&self, is_solid: impl Fn(&T) -> bool, is_open: impl Fn(Voxel) -> bool) -> usize {
+        (0..self.tiles.len())
+            .filter(|&idx| is_solid(&self.tiles[idx]))
+            .map(|idx| {
+                self.idx2pos(idx)
+                    .neighbors6()
+                    .iter()
+                    .filter(|pos| self.try_pos2idx(pos).is_none_or(&is_open))
+                    .count()
+            })
+            .sum()
+    }
+}
+
+/// A cuboid spanning from `min` to `max` (both included), falling along the `z` axis.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Brick {
+    pub min: Pos3D,
+    pub max: Pos3D,
+}
+
+impl Brick {
+    /// Creates a brick from two opposite corners, in any order.
+    pub fn new(a: Pos3D, b: Pos3D) -> Self {
+        Brick {
+            min: Pos3D::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Pos3D::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    fn footprint(&self) -> impl Iterator<Item = (i32, i32)> + use<> {
+        let (min, max) = (self.min, self.max);
+        (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| (x, y)))
+    }
+}
+
+/// Bricks after they all fell down, see [`Settled::settle`].
+#[derive(Debug, Clone)]
+pub struct Settled {
+    /// Resting bricks, in the same order as the input.
+    pub bricks: Vec<Brick>,
+    /// Indices of the bricks directly below (and touching) every brick.
+    pub supported_by: Vec<Vec<usize>>,
+    /// Indices of the bricks directly above (and touching) every brick.
+    pub supports: Vec<Vec<usize>>,
+}
+
+impl Settled {
+    /// Lets every brick fall until it rests on the ground (`z = 0`, so the lowest bricks end
+    /// up at `z = 1`) or on another brick.
+    pub fn settle(bricks: &[Brick]) -> Self {
+        let mut order: Vec<usize> = (0..bricks.len()).collect();
+        order.sort_by_key(|&i| bricks[i].min.z);
+
+        let mut settled = bricks.to_vec();
+        let mut supported_by = vec![vec![]; bricks.len()];
+        let mut supports = vec![vec![]; bricks.len()];
+        // highest occupied z (and the brick occupying it) for every (x, y) column
+        let mut tops: HashMap<(i32, i32), (i32, usize)> = HashMap::new();
+
+        for i in order {
+            let brick = bricks[i];
+            let rest_on = brick
+                .footprint()
+                .filter_map(|column| tops.get(&column))
+                .map(|&(z, _)| z)
+                .max()
+                .unwrap_or(0);
+
+            for column in brick.footprint() {
+                if let Some(&(z, below)) = tops.get(&column)
+                    && z == rest_on
+                    && !supported_by[i].contains(&below)
+                {
+                    supported_by[i].push(below);
+                    supports[below].push(i);
+                }
+            }
+
+            let drop = brick.min.z - (rest_on + 1);
+            settled[i].min.z -= drop;
+            settled[i].max.z -= drop;
+            for column in brick.footprint() {
+                tops.insert(column, (settled[i].max.z, i));
+            }
+        }
+
+        Settled {
+            bricks: settled,
+            supported_by,
+            supports,
+        }
+    }
+
+    /// Whether brick `i` can be removed without any other brick falling.
+    pub fn can_remove(&self, i: usize) -> bool {
+        self.supports[i]
+            .iter()
+            .all(|&above| self.supported_by[above].len() > 1)
+    }
+
+    /// Number of other bricks that would fall if brick `i` was removed.
+    pub fn chain_reaction(&self, i: usize) -> usize {
+        let mut fallen = vec![false; self.bricks.len()];
+        fallen[i] = true;
+        let mut queue = VecDeque::from([i]);
+        let mut count = 0;
+
+        while let Some(current) = queue.pop_front() {
+            for &above in &self.supports[current] {
+                if !fallen[above] && self.supported_by[above].iter().all(|&b| fallen[b]) {
+                    fallen[above] = true;
+                    count += 1;
+                    queue.push_back(above);
+                }
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Brick, Map3D, Settled};
+    use crate::utils::Pos3D;
+
+    fn parse_points(input: &str) -> Vec<Pos3D> {
+        input.lines().map(|line| line.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn measures_surface_area() {
+        let points = parse_points(
+            "2,2,2\n1,2,2\n3,2,2\n2,1,2\n2,3,2\n2,2,1\n2,2,3\n2,2,4\n2,2,6\n1,2,5\n3,2,5\n2,1,5\n2,3,5",
+        );
+        let map = Map3D::from_points(&points, false, true);
+        assert_eq!(map.surface_area(|&solid| solid), 64);
+        assert_eq!(map.exterior_surface_area(|&solid| solid), 58);
+        assert_eq!(
+            map.flood_fill_by(map.pos2idx(&points[0]), false, |&s| s)
+                .len(),
+            8
+        );
+    }
+
+    #[test]
+    fn neighbor_indices_match_positions() {
+        let map = Map3D::new(3, 2, 4, ());
+        for idx in 0..map.tiles.len() {
+            for include_corners in [false, true] {
+                let expected: Vec<_> = map
+                    .idx2pos(idx)
+                    .neighbors(include_corners)
+                    .iter()
+                    .filter_map(|pos| map.try_pos2idx(pos))
+                    .collect();
+                let actual: Vec<_> = map.neighbor_indices(idx, include_corners).collect();
+                assert_eq!(actual, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn settles_bricks() {
+        let bricks: Vec<Brick> = "1,0,1~1,2,1\n0,0,2~2,0,2\n0,2,3~2,2,3\n0,0,4~0,2,4\n2,0,5~2,2,5\n0,1,6~2,1,6\n1,1,8~1,1,9"
+            .lines()
+            .map(|line| {
+                let (a, b) = line.split_once('~').unwrap();
+                Brick::new(a.parse().unwrap(), b.parse().unwrap())
+            })
+            .collect();
+
+        let settled = Settled::settle(&bricks);
+        let removable = (0..bricks.len()).filter(|&i| settled.can_remove(i)).count();
+        assert_eq!(removable, 5);
+        let falling: usize = (0..bricks.len()).map(|i| settled.chain_reaction(i)).sum();
+        assert_eq!(falling, 7);
+        assert_eq!(settled.bricks[6].min.z, 5);
+    }
+}
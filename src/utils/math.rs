@@ -0,0 +1,327 @@
+use std::{
+    fmt::{Debug, Display},
+    hash::Hash,
+    ops::{Add, Div, Mul, Rem, Sub},
+};
+
+/// Primitive integers usable with the number theory helpers of this module.
+///
+/// Modular helpers expect positive moduli and work on residues in `0..m`, so signed and
+/// unsigned types behave the same. Signed types extend it as [`super::Coord`] for positions.
+pub trait Integer:
+    Copy
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TWO: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn abs(self) -> Self;
+    /// Remainder in `0..m`, even for negative values.
+    fn rem_euclid(self, m: Self) -> Self;
+    /// `self * rhs % m` without overflowing, for `self` and `rhs` in `0..m`.
+    fn mul_mod(self, rhs: Self, m: Self) -> Self;
+    fn from_usize(value: usize) -> Option<Self>;
+    fn to_usize(self) -> Option<usize>;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty => $abs:expr, $mul_mod:expr);* $(;)?) => {
+        $(
+            impl Integer for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const TWO: Self = 2;
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn abs(self) -> Self {
+                    $abs(self)
+                }
+
+                fn rem_euclid(self, m: Self) -> Self {
+                    <$t>::rem_euclid(self, m)
+                }
+
+                fn mul_mod(self, rhs: Self, m: Self) -> Self {
+                    $mul_mod(self, rhs, m)
+                }
+
+                fn from_usize(value: usize) -> Option<Self> {
+                    Self::try_from(value).ok()
+                }
+
+                fn to_usize(self) -> Option<usize> {
+                    usize::try_from(self).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(
+    u32 => |x| x, |a, b, m| (a as u64 * b as u64 % m as u64) as u32;
+    u64 => |x| x, |a, b, m| (a as u128 * b as u128 % m as u128) as u64;
+    usize => |x| x, |a, b, m| (a as u128 * b as u128 % m as u128) as usize;
+    u128 => |x| x, mul_mod_u128;
+    i32 => i32::abs, |a, b, m| (a as i64 * b as i64 % m as i64) as i32;
+    i64 => i64::abs, |a, b, m| (a as i128 * b as i128 % m as i128) as i64;
+    isize => isize::abs, |a, b, m| (a as i128 * b as i128 % m as i128) as isize;
+    i128 => i128::abs, |a: i128, b: i128, m: i128| {
+        mul_mod_u128(a as u128, b as u128, m as u128) as i128
+    };
+);
+
+/// Double-and-add multiplication, nothing wider to fall back on.
+fn mul_mod_u128(mut a: u128, mut b: u128, m: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+/// `(a + b) % m` for `a` and `b` in `0..m`, without overflowing.
+fn add_mod<T: Integer>(a: T, b: T, m: T) -> T {
+    if a >= m - b { a - (m - b) } else { a + b }
+}
+
+/// Greatest common divisor, always non-negative.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, always non-negative.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    a.abs() / gcd(a, b) * b.abs()
+}
+
+/// GCD of every value, 0 for an empty iterator.
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::ZERO, gcd)
+}
+
+/// LCM of every value, 1 for an empty iterator.
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::ONE, lcm)
+}
+
+/// Returns `(g, x, y)` such that `a * x + b * y = g = gcd(a, b)`.
+pub fn extended_gcd<T>(a: T, b: T) -> (T, T, T)
+where
+    T: Integer + std::ops::Neg<Output = T>,
+{
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < T::ZERO {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `x` in `0..m` such that `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inverse<T: Integer>(a: T, m: T) -> Option<T> {
+    if m == T::ONE {
+        return Some(T::ZERO);
+    }
+    // extended Euclid, keeping the coefficients of `a` modulo `m` so they never go negative
+    let (mut old_r, mut r) = (m, a.rem_euclid(m));
+    let (mut old_t, mut t) = (T::ZERO, T::ONE);
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        let qt = (q % m).mul_mod(t, m);
+        (old_t, t) = (t, add_mod(old_t, m - qt, m) % m);
+    }
+    (old_r == T::ONE).then_some(old_t)
+}
+
+/// `base ^ exp % m`, by squaring.
+pub fn mod_pow<T: Integer>(base: T, mut exp: T, m: T) -> T {
+    let mut base = base.rem_euclid(m);
+    let mut result = T::ONE % m;
+    while exp > T::ZERO {
+        if exp % T::TWO == T::ONE {
+            result = result.mul_mod(base, m);
+        }
+        base = base.mul_mod(base, m);
+        exp = exp / T::TWO;
+    }
+    result
+}
+
+/// Solves `x ≡ r (mod m)` for every `(r, m)` pair, the moduli don't need to be coprime.
+///
+/// Returns `(x, lcm)` with `x` in `0..lcm`, or `None` if the congruences contradict each other.
+pub fn crt<T: Integer>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    congruences
+        .into_iter()
+        .try_fold((T::ZERO, T::ONE), |(a1, m1), (a2, m2)| {
+            let a2 = a2.rem_euclid(m2);
+            let g = gcd(m1, m2);
+            // x = a1 + k * m1, with k * m1 ≡ a2 - a1 (mod m2)
+            let diff = add_mod(a2, m2 - a1 % m2, m2) % m2;
+            if diff % g != T::ZERO {
+                return None;
+            }
+            let m2g = m2 / g;
+            let k = (diff / g).mul_mod(mod_inverse(m1 / g, m2g)?, m2g);
+            let lcm = m1 / g * m2;
+            Some((add_mod(a1, k.mul_mod(m1 % lcm, lcm), lcm), lcm))
+        })
+}
+
+/// Every prime up to `limit` (included), with a sieve of Eratosthenes.
+pub fn primes<T: Integer>(limit: T) -> Vec<T> {
+    let Some(limit) = limit.to_usize() else {
+        return vec![];
+    };
+    let mut is_prime = vec![true; limit + 1];
+    let mut primes = vec![];
+    for n in 2..=limit {
+        if !is_prime[n] {
+            continue;
+        }
+        primes.push(T::from_usize(n).unwrap());
+        for multiple in (n * n..=limit).step_by(n) {
+            is_prime[multiple] = false;
+        }
+    }
+    primes
+}
+
+/// Prime factors of `n` with their exponents, in increasing order (trial division).
+pub fn factorize<T: Integer>(n: T) -> Vec<(T, u32)> {
+    let mut n = n.abs();
+    let mut factors = vec![];
+    if n == T::ZERO {
+        return factors;
+    }
+    let mut divide = |n: &mut T, p: T| {
+        let mut exp = 0;
+        while *n % p == T::ZERO {
+            *n = *n / p;
+            exp += 1;
+        }
+        if exp > 0 {
+            factors.push((p, exp));
+        }
+    };
+
+    divide(&mut n, T::TWO);
+    let mut p = T::TWO + T::ONE;
+    while p <= n / p {
+        divide(&mut n, p);
+        p = p + T::TWO;
+    }
+    if n > T::ONE {
+        factors.push((n, 1));
+    }
+    factors
+}
+
+/// Every positive divisor of `n`, 1 and `n` included, in increasing order.
+pub fn divisors<T: Integer>(n: T) -> Vec<T> {
+    if n == T::ZERO {
+        return vec![];
+    }
+    let mut divisors = vec![T::ONE];
+    for (p, exp) in factorize(n) {
+        let mut next = Vec::with_capacity(divisors.len() * (exp as usize + 1));
+        for &d in &divisors {
+            let mut power = d;
+            next.push(power);
+            for _ in 0..exp {
+                power = power * p;
+                next.push(power);
+            }
+        }
+        divisors = next;
+    }
+    divisors.sort_unstable();
+    divisors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        crt, divisors, extended_gcd, factorize, gcd, gcd_all, lcm_all, mod_inverse, mod_pow, primes,
+    };
+
+    #[test]
+    fn computes_gcd_and_lcm() {
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd_all([12u64, 18, 27]), 3);
+        assert_eq!(lcm_all([4u64, 6, 10]), 60);
+        assert_eq!(lcm_all(Vec::<u128>::new()), 1);
+
+        let (g, x, y) = extended_gcd(240i64, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+    }
+
+    #[test]
+    fn does_modular_arithmetic() {
+        assert_eq!(mod_inverse(3u64, 11), Some(4));
+        assert_eq!(mod_inverse(-3i64, 11), Some(7));
+        assert_eq!(mod_inverse(6u64, 9), None);
+        assert_eq!(mod_pow(2u64, 10, 1000), 24);
+        assert_eq!(mod_pow(3i64, 0, 1), 0);
+
+        // close to the type's limits, through the double-and-add fallback
+        let m = u128::MAX - 158;
+        assert_eq!(mod_pow(m - 1, 2, m), 1);
+        assert_eq!(mod_inverse(m - 1, m), Some(m - 1));
+    }
+
+    #[test]
+    fn solves_congruences() {
+        assert_eq!(crt([(2u64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // non-coprime moduli
+        assert_eq!(crt([(2i64, 6), (8, 10)]), Some((8, 30)));
+        assert_eq!(crt([(1u64, 4), (2, 6)]), None);
+        assert_eq!(crt([(-1i64, 4), (3, 6)]), Some((3, 12)));
+    }
+
+    #[test]
+    fn factorizes() {
+        assert_eq!(primes(30u64), [2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(factorize(360u64), [(2, 3), (3, 2), (5, 1)]);
+        assert_eq!(factorize(600_851_475_143i64).last(), Some(&(6857, 1)));
+        assert_eq!(factorize(1_000_000_007u128), [(1_000_000_007, 1)]);
+        assert_eq!(divisors(12u64), [1, 2, 3, 4, 6, 12]);
+        assert_eq!(divisors(1u64), [1]);
+    }
+}
@@ -1,10 +1,61 @@
+pub mod automaton;
+pub mod corridor;
+pub mod cycle;
 pub mod direction;
+pub mod flow;
+pub mod graph;
+pub mod hex;
+pub mod image;
+pub mod interval;
 pub mod map;
+pub mod map3d;
+pub mod math;
 pub mod position;
+pub mod position3d;
+pub mod region;
+pub mod render;
+pub mod search;
+pub mod sparse;
+pub mod stepper;
+pub mod transform;
 
+pub use self::automaton::Automaton;
+pub use self::corridor::JunctionGraph;
+pub use self::cycle::Cycle;
 pub use self::direction::Direction;
+pub use self::direction::Direction8;
+pub use self::direction::Turn;
+pub use self::flow::Cut;
+pub use self::graph::CycleError;
+pub use self::graph::Graph;
+pub use self::hex::Hex;
+pub use self::hex::HexDirection;
+pub use self::hex::HexLayout;
+pub use self::hex::HexMap;
+pub use self::image::Image;
+pub use self::interval::IntervalSet;
 pub use self::map::AStar;
+pub use self::map::Goal;
 pub use self::map::Map2D;
+pub use self::map::Map2DError;
 pub use self::map::Node;
+pub use self::map::OptimalPaths;
+pub use self::map3d::Brick;
+pub use self::map3d::Map3D;
+pub use self::map3d::Settled;
+pub use self::position::Coord;
+pub use self::position::Point2D;
 pub use self::position::Pos2D;
+pub use self::position::Pos2D64;
 pub use self::position::Pos2DError;
+pub use self::position3d::Point3D;
+pub use self::position3d::Pos3D;
+pub use self::position3d::Pos3DError;
+pub use self::region::Region;
+pub use self::region::Regions;
+pub use self::render::Overlay;
+pub use self::render::Renderer;
+pub use self::search::SearchResult;
+pub use self::sparse::SparseGrid;
+pub use self::stepper::Stepper;
+pub use self::transform::Window;
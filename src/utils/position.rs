@@ -1,10 +1,75 @@
-use super::Direction;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use super::{Direction, Direction8, math::Integer};
 use anyhow::Result;
 
+/// Signed integer types that can be used as [`Point2D`] coordinates.
+///
+/// Arithmetic goes through the regular operators, so it is overflow-checked in debug builds.
+pub trait Coord: Integer + Neg<Output = Self> + AddAssign + SubAssign {
+    fn from_i32(value: i32) -> Self;
+    fn to_f64(self) -> f64;
+
+    fn signum(self) -> Self {
+        match self.cmp(&Self::ZERO) {
+            std::cmp::Ordering::Less => -Self::ONE,
+            std::cmp::Ordering::Equal => Self::ZERO,
+            std::cmp::Ordering::Greater => Self::ONE,
+        }
+    }
+}
+
+macro_rules! impl_coord {
+    ($($t:ty),*) => {
+        $(
+            impl Coord for $t {
+                fn from_i32(value: i32) -> Self {
+                    value as $t
+                }
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+            }
+        )*
+    };
+}
+
+impl_coord!(i32, i64, i128, isize);
+
+/// A 2D position, generic over its coordinate type.
+///
+/// Use the [`Pos2D`] alias for grids and [`Pos2D64`] when coordinates can grow huge.
 #[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
-pub struct Pos2D {
-    pub x: i32,
-    pub y: i32,
+pub struct Point2D<T> {
+    pub x: T,
+    pub y: T,
+}
+
+pub type Pos2D = Point2D<i32>;
+pub type Pos2D64 = Point2D<i64>;
+
+/// Neighbor offsets, in the same row by row order as [`Point2D::neighbors`].
+pub const NEIGHBORS_4: [(i32, i32); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+pub const NEIGHBORS_8: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// [`NEIGHBORS_8`] with corners, [`NEIGHBORS_4`] without: the one place where 4-way and
+/// 8-way adjacency is chosen, for positions and tile indices alike.
+pub fn neighbor_offsets(include_corners: bool) -> &'static [(i32, i32)] {
+    if include_corners {
+        &NEIGHBORS_8
+    } else {
+        &NEIGHBORS_4
+    }
 }
 
 #[derive(Debug)]
@@ -12,98 +77,269 @@ pub enum Pos2DError {
     OutOfBounds(String),
 }
 
-impl Pos2D {
-    pub fn new(x: i32, y: i32) -> Pos2D {
-        Pos2D { x, y }
+impl<T> Point2D<T> {
+    pub const fn new(x: T, y: T) -> Point2D<T> {
+        Point2D { x, y }
+    }
+}
+
+impl<T: Coord> Point2D<T> {
+    fn offset(&self, (dx, dy): (i32, i32)) -> Point2D<T> {
+        Point2D::new(self.x + T::from_i32(dx), self.y + T::from_i32(dy))
     }
 
-    pub fn from_idx(idx: usize, width: usize, height: usize) -> Result<Pos2D, Pos2DError> {
-        let x = (idx % width) as i32;
-        let y = (idx / width) as i32;
+    pub fn from_idx(idx: usize, width: usize, height: usize) -> Result<Point2D<T>, Pos2DError> {
+        let (x, y) = (idx % width, idx / width);
 
-        if x < 0 || x >= (width as i32) || y < 0 || y >= (height as i32) {
-            let msg = format!("Idx out of bounds: {} => (x: {}, y: {})", idx, x, y);
-            return Err(Pos2DError::OutOfBounds(msg));
+        match (T::from_usize(x), T::from_usize(y)) {
+            (Some(px), Some(py)) if y < height => Ok(Point2D::new(px, py)),
+            _ => {
+                let msg = format!("Idx out of bounds: {} => (x: {}, y: {})", idx, x, y);
+                Err(Pos2DError::OutOfBounds(msg))
+            }
         }
-
-        Ok(Pos2D::new(x, y))
     }
 
     pub fn to_idx(&self, width: usize, height: usize) -> Result<usize, Pos2DError> {
-        if self.x < 0 || self.x >= (width as i32) || self.y < 0 || self.y >= (height as i32) {
-            let msg = format!("Pos out of bounds: (x: {}, y: {})", self.x, self.y);
-            return Err(Pos2DError::OutOfBounds(msg));
+        match (self.x.to_usize(), self.y.to_usize()) {
+            (Some(x), Some(y)) if x < width && y < height => Ok(y * width + x),
+            _ => {
+                let msg = format!("Pos out of bounds: (x: {}, y: {})", self.x, self.y);
+                Err(Pos2DError::OutOfBounds(msg))
+            }
         }
+    }
 
-        let idx = self.y as usize * width + self.x as usize;
-        Ok(idx)
+    pub fn right(&self, offset: T) -> Point2D<T> {
+        Point2D::new(self.x + offset, self.y)
     }
 
-    pub fn right(&self, offset: i32) -> Pos2D {
-        Pos2D::new(self.x + offset, self.y)
+    pub fn left(&self, offset: T) -> Point2D<T> {
+        Point2D::new(self.x - offset, self.y)
     }
 
-    pub fn left(&self, offset: i32) -> Pos2D {
-        Pos2D::new(self.x - offset, self.y)
+    pub fn up(&self, offset: T) -> Point2D<T> {
+        Point2D::new(self.x, self.y - offset)
     }
 
-    pub fn up(&self, offset: i32) -> Pos2D {
-        Pos2D::new(self.x, self.y - offset)
+    pub fn down(&self, offset: T) -> Point2D<T> {
+        Point2D::new(self.x, self.y + offset)
     }
 
-    pub fn down(&self, offset: i32) -> Pos2D {
-        Pos2D::new(self.x, self.y + offset)
+    /// Direction of the (possibly diagonal) step towards `next`, `None` if both are equal.
+    ///
+    /// Only the signs of the offsets count, so `next` doesn't have to be adjacent.
+    pub fn get_direction(&self, next: Point2D<T>) -> Option<Direction8> {
+        let step = (next - *self).signum();
+        Direction8::ALL
+            .into_iter()
+            .find(|dir| Point2D::new(T::ZERO, T::ZERO).offset(dir.delta().into()) == step)
     }
 
-    pub fn get_direction(&self, next: Pos2D) -> Direction {
-        if self.x == next.x {
-            if next.y < self.y {
-                Direction::North
-            } else {
-                Direction::South
-            }
-        } else {
-            if next.x > self.x {
-                Direction::East
-            } else {
-                Direction::West
-            }
-        }
+    pub fn neighbors(&self, include_corners: bool) -> Vec<Point2D<T>> {
+        self.neighbors_iter(include_corners).collect()
     }
 
-    pub fn neighbors(&self, include_corners: bool) -> Vec<Pos2D> {
-        let mut neighbors = vec![];
-        for y in self.y - 1..=self.y + 1 {
-            for x in self.x - 1..=self.x + 1 {
-                if !include_corners && y != self.y && x != self.x {
-                    continue;
-                }
+    /// Same as [`Point2D::neighbors`], without allocating.
+    pub fn neighbors_iter(
+        &self,
+        include_corners: bool,
+    ) -> impl Iterator<Item = Point2D<T>> + use<T> {
+        let origin = *self;
+        neighbor_offsets(include_corners)
+            .iter()
+            .map(move |&offset| origin.offset(offset))
+    }
 
-                // skip self
-                if x == self.x && y == self.y {
-                    continue;
-                }
+    /// The 4 orthogonal neighbors: north, west, east, south.
+    pub fn neighbors4(&self) -> [Point2D<T>; 4] {
+        NEIGHBORS_4.map(|offset| self.offset(offset))
+    }
 
-                neighbors.push(Pos2D::new(x, y));
-            }
+    /// The 8 surrounding positions, row by row from the top left.
+    pub fn neighbors8(&self) -> [Point2D<T>; 8] {
+        NEIGHBORS_8.map(|offset| self.offset(offset))
+    }
+
+    /// Component-wise sign: every coordinate becomes -1, 0 or 1.
+    pub fn signum(&self) -> Point2D<T> {
+        Point2D::new(self.x.signum(), self.y.signum())
+    }
+
+    pub fn dist(&self, other: &Point2D<T>) -> f64 {
+        let xd = (other.x - self.x).to_f64();
+        let yd = (other.y - self.y).to_f64();
+        (xd * xd + yd * yd).sqrt()
+    }
+
+    pub fn manhattan(&self, other: &Point2D<T>) -> T {
+        (other.x - self.x).abs() + (other.y - self.y).abs()
+    }
+
+    pub fn chebyshev(&self, other: &Point2D<T>) -> T {
+        (other.x - self.x).abs().max((other.y - self.y).abs())
+    }
+}
+
+impl<T: Coord> Add for Point2D<T> {
+    type Output = Point2D<T>;
+
+    fn add(self, rhs: Point2D<T>) -> Self::Output {
+        Point2D::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T: Coord> Add<Direction> for Point2D<T> {
+    type Output = Point2D<T>;
+
+    fn add(self, rhs: Direction) -> Self::Output {
+        self + Point2D::from(rhs)
+    }
+}
+
+impl<T: Coord> AddAssign for Point2D<T> {
+    fn add_assign(&mut self, rhs: Point2D<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Coord> AddAssign<Direction> for Point2D<T> {
+    fn add_assign(&mut self, rhs: Direction) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Coord> Sub for Point2D<T> {
+    type Output = Point2D<T>;
+
+    fn sub(self, rhs: Point2D<T>) -> Self::Output {
+        Point2D::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T: Coord> SubAssign for Point2D<T> {
+    fn sub_assign(&mut self, rhs: Point2D<T>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Coord> Neg for Point2D<T> {
+    type Output = Point2D<T>;
+
+    fn neg(self) -> Self::Output {
+        Point2D::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Coord> Mul<T> for Point2D<T> {
+    type Output = Point2D<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point2D::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T> From<(T, T)> for Point2D<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Point2D::new(x, y)
+    }
+}
+
+impl<T> From<Point2D<T>> for (T, T) {
+    fn from(value: Point2D<T>) -> Self {
+        (value.x, value.y)
+    }
+}
+
+impl From<Pos2D> for Pos2D64 {
+    fn from(value: Pos2D) -> Self {
+        Pos2D64::new(value.x.into(), value.y.into())
+    }
+}
+
+impl TryFrom<Pos2D64> for Pos2D {
+    type Error = Pos2D64;
+
+    /// Fails if any coordinate doesn't fit in an `i32`.
+    fn try_from(value: Pos2D64) -> Result<Self, Self::Error> {
+        match (i32::try_from(value.x), i32::try_from(value.y)) {
+            (Ok(x), Ok(y)) => Ok(Pos2D::new(x, y)),
+            _ => Err(value),
+        }
+    }
+}
+
+/// Unit vector pointing towards the direction, with north being up (negative y).
+impl<T: Coord> From<Direction> for Point2D<T> {
+    fn from(value: Direction) -> Self {
+        let (zero, one) = (T::ZERO, T::ONE);
+        match value {
+            Direction::North => Point2D::new(zero, -one),
+            Direction::East => Point2D::new(one, zero),
+            Direction::South => Point2D::new(zero, one),
+            Direction::West => Point2D::new(-one, zero),
         }
-        neighbors
+    }
+}
+
+impl<T: Coord> TryFrom<Point2D<T>> for Direction {
+    type Error = Point2D<T>;
+
+    /// Converts a unit vector back into a direction, fails for any other vector.
+    fn try_from(value: Point2D<T>) -> Result<Self, Self::Error> {
+        Direction::ALL
+            .into_iter()
+            .find(|&dir| Point2D::from(dir) == value)
+            .ok_or(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Pos2D, Pos2D64};
+    use crate::utils::Direction;
+
+    #[test]
+    fn supports_arithmetic() {
+        let mut pos = Pos2D::new(1, 2) + Pos2D::new(3, -4) - Pos2D::new(1, 1);
+        assert_eq!(pos, Pos2D::new(3, -3));
+        pos += Direction::North;
+        assert_eq!(-pos * 2, Pos2D::new(-6, 8));
+        assert_eq!(Pos2D::from((5, 6)), Pos2D::new(5, 6));
+        assert_eq!(<(i32, i32)>::from(pos), (3, -4));
     }
 
-    pub fn dist(&self, other: &Pos2D) -> f64 {
-        let xd = (other.x - self.x).pow(2) as f64;
-        let yd = (other.y - self.y).pow(2) as f64;
-        (xd + yd).sqrt()
+    #[test]
+    fn measures_distances() {
+        let (a, b) = (Pos2D::new(0, 0), Pos2D::new(3, -4));
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(a.dist(&b), 5.);
     }
 
-    pub fn add<T>(&self, other: T) -> Pos2D
-    where
-        T: AsRef<Pos2D>,
-    {
-        let other = other.as_ref();
-        Pos2D {
-            x: self.x + other.x,
-            y: self.y + other.y,
+    #[test]
+    fn converts_directions() {
+        for dir in [
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ] {
+            assert_eq!(Direction::try_from(Pos2D::from(dir)).unwrap(), dir);
         }
+        assert!(Direction::try_from(Pos2D::new(1, 1)).is_err());
+    }
+
+    #[test]
+    fn supports_64_bit_coordinates() {
+        let a = Pos2D64::new(-3_000_000_000, 7);
+        let b = Pos2D64::from(Pos2D::new(2, -5)) * 2_000_000_000;
+        assert_eq!(a.manhattan(&b), 7_000_000_000 + 10_000_000_007);
+        assert!(Pos2D::try_from(b).is_err());
+
+        let pos = Pos2D64::from_idx(7, 3, 3).unwrap();
+        assert_eq!(pos, Pos2D64::new(1, 2));
+        assert_eq!(pos.to_idx(3, 3).unwrap(), 7);
+        assert!(Pos2D64::new(3, 0).to_idx(3, 3).is_err());
     }
 }
@@ -0,0 +1,228 @@
+use std::{
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use super::Coord;
+
+/// Offsets of the 6 face-sharing neighbors, see [`Point3D::neighbors6`].
+pub const NEIGHBORS_6: [(i32, i32, i32); 6] = [
+    (-1, 0, 0),
+    (1, 0, 0),
+    (0, -1, 0),
+    (0, 1, 0),
+    (0, 0, -1),
+    (0, 0, 1),
+];
+
+/// Offsets of the 26 surrounding positions, layer by layer and row by row, see
+/// [`Point3D::neighbors26`].
+pub const NEIGHBORS_26: [(i32, i32, i32); 26] = {
+    let mut offsets = [(0, 0, 0); 26];
+    let mut i = 0;
+    let mut d = 0;
+    while d < 27 {
+        // skip the center, d = 13
+        if d != 13 {
+            offsets[i] = (d % 3 - 1, d / 3 % 3 - 1, d / 9 - 1);
+            i += 1;
+        }
+        d += 1;
+    }
+    offsets
+};
+
+/// A 3D position, generic over its coordinate type. See [`Pos3D`] for the `i32` flavor.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug)]
+pub struct Point3D<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+pub type Pos3D = Point3D<i32>;
+
+#[derive(Debug)]
+pub enum Pos3DError {
+    OutOfBounds(String),
+    Invalid(String),
+}
+
+impl<T> Point3D<T> {
+    pub const fn new(x: T, y: T, z: T) -> Point3D<T> {
+        Point3D { x, y, z }
+    }
+}
+
+impl<T: Coord> Point3D<T> {
+    pub fn from_idx(
+        idx: usize,
+        width: usize,
+        height: usize,
+        depth: usize,
+    ) -> Result<Point3D<T>, Pos3DError> {
+        let (x, y, z) = (idx % width, idx / width % height, idx / (width * height));
+
+        match (T::from_usize(x), T::from_usize(y), T::from_usize(z)) {
+            (Some(px), Some(py), Some(pz)) if z < depth => Ok(Point3D::new(px, py, pz)),
+            _ => {
+                let msg = format!("Idx out of bounds: {idx} => (x: {x}, y: {y}, z: {z})");
+                Err(Pos3DError::OutOfBounds(msg))
+            }
+        }
+    }
+
+    pub fn to_idx(&self, width: usize, height: usize, depth: usize) -> Result<usize, Pos3DError> {
+        match (self.x.to_usize(), self.y.to_usize(), self.z.to_usize()) {
+            (Some(x), Some(y), Some(z)) if x < width && y < height && z < depth => {
+                Ok((z * height + y) * width + x)
+            }
+            _ => {
+                let msg = format!(
+                    "Pos out of bounds: (x: {}, y: {}, z: {})",
+                    self.x, self.y, self.z
+                );
+                Err(Pos3DError::OutOfBounds(msg))
+            }
+        }
+    }
+
+    /// The 6 face-sharing neighbors.
+    pub fn neighbors6(&self) -> [Point3D<T>; 6] {
+        NEIGHBORS_6.map(|offset| self.offset(offset))
+    }
+
+    /// The 26 surrounding positions, sharing a face, an edge or a corner.
+    pub fn neighbors26(&self) -> [Point3D<T>; 26] {
+        NEIGHBORS_26.map(|offset| self.offset(offset))
+    }
+
+    fn offset(&self, (dx, dy, dz): (i32, i32, i32)) -> Point3D<T> {
+        *self + Point3D::new(T::from_i32(dx), T::from_i32(dy), T::from_i32(dz))
+    }
+
+    /// Same as [`Point3D::neighbors6`] or [`Point3D::neighbors26`], as a `Vec`.
+    pub fn neighbors(&self, include_corners: bool) -> Vec<Point3D<T>> {
+        if include_corners {
+            self.neighbors26().to_vec()
+        } else {
+            self.neighbors6().to_vec()
+        }
+    }
+
+    pub fn dist(&self, other: &Point3D<T>) -> f64 {
+        let d = *other - *self;
+        let (xd, yd, zd) = (d.x.to_f64(), d.y.to_f64(), d.z.to_f64());
+        (xd * xd + yd * yd + zd * zd).sqrt()
+    }
+
+    pub fn manhattan(&self, other: &Point3D<T>) -> T {
+        (other.x - self.x).abs() + (other.y - self.y).abs() + (other.z - self.z).abs()
+    }
+}
+
+impl<T: Coord> Add for Point3D<T> {
+    type Output = Point3D<T>;
+
+    fn add(self, rhs: Point3D<T>) -> Self::Output {
+        Point3D::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl<T: Coord> AddAssign for Point3D<T> {
+    fn add_assign(&mut self, rhs: Point3D<T>) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: Coord> Sub for Point3D<T> {
+    type Output = Point3D<T>;
+
+    fn sub(self, rhs: Point3D<T>) -> Self::Output {
+        Point3D::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl<T: Coord> SubAssign for Point3D<T> {
+    fn sub_assign(&mut self, rhs: Point3D<T>) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: Coord> Neg for Point3D<T> {
+    type Output = Point3D<T>;
+
+    fn neg(self) -> Self::Output {
+        Point3D::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Coord> Mul<T> for Point3D<T> {
+    type Output = Point3D<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point3D::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3D<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point3D::new(x, y, z)
+    }
+}
+
+impl<T> From<Point3D<T>> for (T, T, T) {
+    fn from(value: Point3D<T>) -> Self {
+        (value.x, value.y, value.z)
+    }
+}
+
+impl<T: FromStr> FromStr for Point3D<T> {
+    type Err = Pos3DError;
+
+    /// Parses `x,y,z`, ignoring whitespace around the coordinates.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Pos3DError::Invalid(format!("expected x,y,z but got: {s}"));
+        let mut coords = s.split(',').map(|c| c.trim().parse::<T>());
+
+        match (coords.next(), coords.next(), coords.next(), coords.next()) {
+            (Some(Ok(x)), Some(Ok(y)), Some(Ok(z)), None) => Ok(Point3D::new(x, y, z)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::Pos3D;
+
+    #[test]
+    fn lists_neighbors() {
+        let origin = Pos3D::new(0, 0, 0);
+        assert_eq!(origin.neighbors(false).len(), 6);
+        let all: HashSet<_> = origin.neighbors26().into_iter().collect();
+        assert_eq!(all.len(), 26);
+        assert!(!all.contains(&origin));
+        assert!(all.contains(&Pos3D::new(-1, 1, -1)));
+    }
+
+    #[test]
+    fn parses_and_measures() {
+        let a: Pos3D = "1, -2,3".parse().unwrap();
+        assert_eq!(a, Pos3D::new(1, -2, 3));
+        assert!("1,2".parse::<Pos3D>().is_err());
+        assert_eq!(a.manhattan(&Pos3D::new(0, 0, 0)), 6);
+        assert_eq!(Pos3D::new(2, 3, 6).dist(&Pos3D::new(0, 0, 0)), 7.);
+        assert_eq!(-a * 2 + Pos3D::new(1, 1, 1), Pos3D::new(-1, 5, -5));
+    }
+
+    #[test]
+    fn converts_indices() {
+        let pos = Pos3D::from_idx(17, 2, 3, 4).unwrap();
+        assert_eq!(pos, Pos3D::new(1, 2, 2));
+        assert_eq!(pos.to_idx(2, 3, 4).unwrap(), 17);
+        assert!(Pos3D::from_idx(24, 2, 3, 4).is_err());
+    }
+}
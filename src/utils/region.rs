@@ -0,0 +1,273 @@
+use super::{Map2D, Pos2D};
+
+/// Offsets of the 4 sides of a tile, in clockwise order starting north.
+const SIDES: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// A connected group of equal tiles.
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub label: usize,
+    pub tiles: Vec<usize>,
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.tiles.len()
+    }
+}
+
+/// Connected-component labelling of a [`Map2D`], see [`Map2D::regions`].
+#[derive(Debug, Clone)]
+pub struct Regions {
+    pub width: usize,
+    pub height: usize,
+    pub wrapping: bool,
+    /// Region label of every tile of the map, indexed like [`Map2D::tiles`].
+    pub labels: Vec<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl Regions {
+    pub fn label_of(&self, tile: usize) -> usize {
+        self.labels[tile]
+    }
+
+    pub fn region_of(&self, tile: usize) -> &Region {
+        &self.regions[self.labels[tile]]
+    }
+
+    fn idx_at(&self, position: Pos2D) -> Option<usize> {
+        if self.wrapping {
+            let x = position.x.rem_euclid(self.width as i32);
+            let y = position.y.rem_euclid(self.height as i32);
+            return Some(y as usize * self.width + x as usize);
+        }
+        position.to_idx(self.width, self.height).ok()
+    }
+
+    fn label_at(&self, position: Pos2D) -> Option<usize> {
+        Some(self.labels[self.idx_at(position)?])
+    }
+
+    /// Whether the side of `tile` facing `offset` lies on the border of its region.
+    fn is_border(&self, tile: usize, offset: (i32, i32)) -> bool {
+        let position = Pos2D::from_idx(tile, self.width, self.height).unwrap();
+        let outside = position + Pos2D::from(offset);
+        self.label_at(outside) != Some(self.labels[tile])
+    }
+
+    /// Number of unit edges between the region and anything else (including the map border).
+    pub fn perimeter(&self, label: usize) -> usize {
+        self.regions[label]
+            .tiles
+            .iter()
+            .map(|&tile| {
+                SIDES
+                    .iter()
+                    .filter(|&&offset| self.is_border(tile, offset))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Number of straight sides of the region's outline (including holes).
+    ///
+    /// On a wrapping map, a side going all the way around the map counts as one side.
+    pub fn sides(&self, label: usize) -> usize {
+        let mut counted = vec![[false; 4]; self.labels.len()];
+        let mut sides = 0;
+        for &tile in &self.regions[label].tiles {
+            for (i, &offset) in SIDES.iter().enumerate() {
+                if counted[tile][i] || !self.is_border(tile, offset) {
+                    continue;
+                }
+
+                // mark the whole side, walking along it both ways from this tile, so that it
+                // is counted once even when it loops back onto itself
+                sides += 1;
+                counted[tile][i] = true;
+                for along in [SIDES[(i + 1) % 4], SIDES[(i + 3) % 4]] {
+                    let mut current = tile;
+                    while let Some(next) = self.next_on_side(current, along, offset)
+                        && !counted[next][i]
+                    {
+                        counted[next][i] = true;
+                        current = next;
+                    }
+                }
+            }
+        }
+        sides
+    }
+
+    /// The tile after `tile` in direction `along`, if it continues the side of `tile`'s
+    /// region facing `offset`.
+    fn next_on_side(&self, tile: usize, along: (i32, i32), offset: (i32, i32)) -> Option<usize> {
+        let position = Pos2D::from_idx(tile, self.width, self.height).unwrap();
+        let next = self.idx_at(position + Pos2D::from(along))?;
+        (self.labels[next] == self.labels[tile] && self.is_border(next, offset)).then_some(next)
+    }
+}
+
+/// Returns every index connected to `start` through indices matching `is_inside`, for any
+/// grid of `len` cells whose adjacency is given by `neighbors`.
+///
+/// `start` is always part of the result, even if it doesn't match `is_inside`.
+pub fn flood_fill<I>(
+    len: usize,
+    start: usize,
+    neighbors: impl Fn(usize) -> I,
+    is_inside: impl Fn(usize) -> bool,
+) -> Vec<usize>
+where
+    I: IntoIterator<Item = usize>,
+{
+    let mut seen = vec![false; len];
+    seen[start] = true;
+    let mut filled = vec![start];
+    let mut stack = vec![start];
+
+    while let Some(current) = stack.pop() {
+        for idx in neighbors(current) {
+            if !seen[idx] && is_inside(idx) {
+                seen[idx] = true;
+                filled.push(idx);
+                stack.push(idx);
+            }
+        }
+    }
+
+    filled
+}
+
+impl<T> Map2D<T>
+where
+    T: PartialEq,
+{
+    /// Returns every tile connected to `start` through tiles equal to it, `start` included.
+    ///
+    /// Tiles are connected 4-way, or 8-way with `include_corners`, exactly like
+    /// [`super::Pos2D::neighbors`]: both take their offsets from
+    /// [`super::position::neighbor_offsets`], through [`Map2D::neighbor_indices`] here.
+    pub fn flood_fill(&self, start: usize, include_corners: bool) -> Vec<usize> {
+        self.flood_fill_by(start, include_corners, |tile| *tile == self.tiles[start])
+    }
+
+    /// Returns every tile connected to `start` through tiles matching `is_inside`, `start`
+    /// included even if it doesn't match, see [`flood_fill`].
+    pub fn flood_fill_by(
+        &self,
+        start: usize,
+        include_corners: bool,
+        is_inside: impl Fn(&T) -> bool,
+    ) -> Vec<usize> {
+        flood_fill(
+            self.tiles.len(),
+            start,
+            |idx| self.neighbor_indices(idx, include_corners),
+            |idx| is_inside(&self.tiles[idx]),
+        )
+    }
+
+    /// Labels every connected region of equal tiles, connected as in [`Map2D::flood_fill`].
+    pub fn regions(&self, include_corners: bool) -> Regions {
+        let mut labels = vec![usize::MAX; self.tiles.len()];
+        let mut regions = vec![];
+
+        for start in 0..self.tiles.len() {
+            if labels[start] != usize::MAX {
+                continue;
+            }
+
+            let label = regions.len();
+            let tiles = self.flood_fill(start, include_corners);
+            for &tile in &tiles {
+                labels[tile] = label;
+            }
+            regions.push(Region { label, tiles });
+        }
+
+        Regions {
+            width: self.width,
+            height: self.height,
+            wrapping: self.wrapping,
+            labels,
+            regions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::Map2D;
+
+    #[test]
+    fn measures_regions() {
+        let map = Map2D::<char>::parse("AAAA\nBBCD\nBBCC\nEEEC\n", &[]).unwrap();
+        let regions = map.regions(false);
+        assert_eq!(regions.regions.len(), 5);
+
+        let measures: Vec<_> = regions
+            .regions
+            .iter()
+            .map(|r| (r.area(), regions.perimeter(r.label), regions.sides(r.label)))
+            .collect();
+        assert_eq!(
+            measures,
+            vec![(4, 10, 4), (4, 8, 4), (4, 10, 8), (1, 4, 4), (3, 8, 4)]
+        );
+    }
+
+    #[test]
+    fn counts_sides_of_concave_regions() {
+        let map = Map2D::<char>::parse("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE\n", &[]).unwrap();
+        let regions = map.regions(false);
+        let e = regions.label_of(0);
+        assert_eq!(regions.region_of(0).area(), 17);
+        assert_eq!(regions.sides(e), 12);
+    }
+
+    #[test]
+    fn counts_sides_of_holes() {
+        // a ring of B around a single A, itself inside a ring of A
+        let map = Map2D::<char>::parse("AAAAA\nABBBA\nABABA\nABBBA\nAAAAA\n", &[]).unwrap();
+        let regions = map.regions(false);
+        let (outer, ring, inner) = (
+            regions.label_of(0),
+            regions.label_of(6),
+            regions.label_of(12),
+        );
+        assert_eq!(regions.regions.len(), 3);
+        assert_eq!(regions.perimeter(outer), 20 + 12);
+        assert_eq!(regions.sides(outer), 4 + 4);
+        assert_eq!(regions.sides(ring), 4 + 4);
+        assert_eq!(regions.sides(inner), 4);
+    }
+
+    #[test]
+    fn measures_wrapping_regions() {
+        // both regions are bands going all the way around the map
+        let map = Map2D::<char>::parse("AAA\nBBB\nBBB\n", &[])
+            .unwrap()
+            .with_wrapping(true);
+        let regions = map.regions(false);
+        let (a, b) = (regions.label_of(0), regions.label_of(3));
+        assert_eq!(regions.regions.len(), 2);
+        assert_eq!((regions.perimeter(a), regions.sides(a)), (6, 2));
+        assert_eq!((regions.perimeter(b), regions.sides(b)), (6, 2));
+
+        // a single wrapping column touching itself is not a side
+        let map = Map2D::<char>::parse("A\nB\n", &[])
+            .unwrap()
+            .with_wrapping(true);
+        let regions = map.regions(false);
+        assert_eq!((regions.perimeter(0), regions.sides(0)), (2, 2));
+    }
+
+    #[test]
+    fn flood_fills_diagonally() {
+        let map = Map2D::<char>::parse("#.\n.#\n", &[]).unwrap();
+        assert_eq!(map.flood_fill(0, false), vec![0]);
+        assert_eq!(map.flood_fill(0, true).len(), 2);
+    }
+}
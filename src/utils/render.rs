@@ -0,0 +1,225 @@
+use std::{
+    fmt::{Display, Write},
+    io::IsTerminal,
+};
+
+use super::{Map2D, Pos2D};
+use crate::template::ANSI_RESET;
+
+type Tile = usize;
+
+/// A set of tiles drawn on top of a [`Map2D`] by a [`Renderer`].
+///
+/// Without a glyph, the tiles underneath are kept and only coloured.
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    pub name: String,
+    tiles: Vec<Tile>,
+    glyph: Option<char>,
+    color: Option<&'static str>,
+}
+
+impl Overlay {
+    /// Highlights every tile of the set, see [`Overlay::glyph`] and [`Overlay::color`].
+    pub fn new(name: impl Into<String>, tiles: impl IntoIterator<Item = Tile>) -> Self {
+        Overlay {
+            name: name.into(),
+            tiles: tiles.into_iter().collect(),
+            glyph: None,
+            color: None,
+        }
+    }
+
+    /// Draws every tile of `path` as `@`, like [`Map2D::display_path`] does.
+    pub fn path(name: impl Into<String>, path: &[Tile]) -> Self {
+        Overlay::new(name, path.iter().copied()).glyph('@')
+    }
+
+    /// Draws a single tile as `glyph`.
+    pub fn marker(name: impl Into<String>, tile: Tile, glyph: char) -> Self {
+        Overlay::new(name, [tile]).glyph(glyph)
+    }
+
+    pub fn glyph(mut self, glyph: char) -> Self {
+        self.glyph = Some(glyph);
+        self
+    }
+
+    /// Colours the overlay with an ANSI escape code such as [`crate::template::ANSI_RED`].
+    pub fn color(mut self, color: &'static str) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// Renders a [`Map2D`] to a `String`, with overlays and an optional viewport.
+///
+/// Colours are only emitted when stdout is a terminal, unless forced with
+/// [`Renderer::colored`].
+#[derive(Debug)]
+pub struct Renderer<'a, T> {
+    map: &'a Map2D<T>,
+    overlays: Vec<Overlay>,
+    viewport: Option<(Pos2D, usize, usize)>,
+    colored: bool,
+}
+
+impl<'a, T: Display> Renderer<'a, T> {
+    pub fn new(map: &'a Map2D<T>) -> Self {
+        Renderer {
+            map,
+            overlays: vec![],
+            viewport: None,
+            colored: std::io::stdout().is_terminal(),
+        }
+    }
+
+    /// Adds an overlay on top of the previous ones, replacing any overlay with the same name.
+    pub fn overlay(mut self, overlay: Overlay) -> Self {
+        self.overlays.retain(|o| o.name != overlay.name);
+        self.overlays.push(overlay);
+        self
+    }
+
+    /// Draws every marker recorded in [`Map2D::markers`] with its own char.
+    pub fn markers(mut self, color: Option<&'static str>) -> Self {
+        let mut chars: Vec<char> = self.map.markers.keys().copied().collect();
+        chars.sort_unstable();
+        for c in chars {
+            let mut overlay = Overlay::new(c, self.map.marker_all(c).iter().copied()).glyph(c);
+            overlay.color = color;
+            self = self.overlay(overlay);
+        }
+        self
+    }
+
+    /// Only renders the `width` x `height` tiles starting at `origin`, cropped to the map.
+    pub fn viewport(mut self, origin: Pos2D, width: usize, height: usize) -> Self {
+        self.viewport = Some((origin, width, height));
+        self
+    }
+
+    pub fn colored(mut self, colored: bool) -> Self {
+        self.colored = colored;
+        self
+    }
+
+    /// Column and row ranges of the tiles to render.
+    fn bounds(&self) -> (std::ops::Range<usize>, std::ops::Range<usize>) {
+        let (width, height) = (self.map.width, self.map.height);
+        match self.viewport {
+            None => (0..width, 0..height),
+            Some((origin, w, h)) => {
+                // intersection of the viewport with the map, along one axis
+                let crop = |start: i32, len: usize, max: usize| {
+                    let lo = (start as i64).clamp(0, max as i64);
+                    let hi = (start as i64 + len as i64).clamp(lo, max as i64);
+                    lo as usize..hi as usize
+                };
+                (crop(origin.x, w, width), crop(origin.y, h, height))
+            }
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let (columns, rows) = self.bounds();
+        let view_width = columns.len();
+
+        // topmost overlay of every visible tile
+        let mut layers: Vec<Option<&Overlay>> = vec![None; view_width * rows.len()];
+        for overlay in &self.overlays {
+            for &tile in &overlay.tiles {
+                let (x, y) = (tile % self.map.width, tile / self.map.width);
+                if columns.contains(&x) && rows.contains(&y) {
+                    layers[(y - rows.start) * view_width + x - columns.start] = Some(overlay);
+                }
+            }
+        }
+
+        let mut out = String::new();
+        for (row, y) in rows.clone().enumerate() {
+            for (column, x) in columns.clone().enumerate() {
+                let tile = &self.map.tiles[y * self.map.width + x];
+                let overlay = layers[row * view_width + column];
+                let color = overlay.and_then(|o| o.color).filter(|_| self.colored);
+
+                if let Some(color) = color {
+                    out.push_str(color);
+                }
+                match overlay.and_then(|o| o.glyph) {
+                    Some(glyph) => out.push(glyph),
+                    None => write!(out, "{tile}").unwrap(),
+                }
+                if color.is_some() {
+                    out.push_str(ANSI_RESET);
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn print(&self) {
+        println!("\n{}", self.render());
+    }
+}
+
+impl<T: Display> Map2D<T> {
+    pub fn renderer(&self) -> Renderer<'_, T> {
+        Renderer::new(self)
+    }
+
+    /// Renders the map as plain text, one line per row.
+    pub fn render(&self) -> String {
+        self.renderer().colored(false).render()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Overlay;
+    use crate::{
+        template::{ANSI_RED, ANSI_RESET},
+        utils::{Map2D, Pos2D},
+    };
+
+    #[test]
+    fn draws_overlays_in_order() {
+        let map = Map2D::<char>::parse("S...\n.#..\n...E\n", &['S', 'E']).unwrap();
+        let rendered = map
+            .renderer()
+            .colored(false)
+            .overlay(Overlay::path("path", &[0, 1, 2, 3, 7, 11]))
+            .markers(None)
+            .render();
+        assert_eq!(rendered, "S@@@\n.#.@\n...E\n");
+        assert_eq!(map.render(), "S...\n.#..\n...E\n");
+    }
+
+    #[test]
+    fn crops_and_colours() {
+        let map = Map2D::<char>::parse("abcd\nefgh\nijkl\n", &[]).unwrap();
+        let renderer = map
+            .renderer()
+            .colored(true)
+            .overlay(Overlay::new("seen", [5, 6]).color(ANSI_RED))
+            .viewport(Pos2D::new(1, -1), 3, 3);
+        assert_eq!(
+            renderer.render(),
+            format!("bcd\n{ANSI_RED}f{ANSI_RESET}{ANSI_RED}g{ANSI_RESET}h\n")
+        );
+
+        // replacing an overlay by name, plain output
+        let renderer = renderer
+            .colored(false)
+            .overlay(Overlay::marker("seen", 9, '*'));
+        assert_eq!(renderer.render(), "bcd\nfgh\n");
+        assert_eq!(
+            map.renderer()
+                .viewport(Pos2D::new(2, 1), 10, 10)
+                .overlay(Overlay::marker("x", 10, '*'))
+                .render(),
+            "gh\n*l\n"
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// Outcome of a graph search: the best known distance to every discovered state and,
+/// if one was reached, the goal state.
+///
+/// Distances are final for every state when the search ran to exhaustion (no goal).
+#[derive(Debug, Clone)]
+pub struct SearchResult<S> {
+    pub goal: Option<S>,
+    pub dist: HashMap<S, u64>,
+    came_from: HashMap<S, S>,
+}
+
+impl<S> SearchResult<S>
+where
+    S: Hash + Eq + Clone,
+{
+    /// Cost of the path to the goal, if one was found.
+    pub fn cost(&self) -> Option<u64> {
+        self.dist.get(self.goal.as_ref()?).copied()
+    }
+
+    /// Path from the start to the goal (both included), if one was found.
+    pub fn path(&self) -> Option<Vec<S>> {
+        self.path_to(self.goal.as_ref()?)
+    }
+
+    /// Path from the start to any discovered `state` (both included).
+    pub fn path_to(&self, state: &S) -> Option<Vec<S>> {
+        if !self.dist.contains_key(state) {
+            return None;
+        }
+
+        let mut path = vec![state.clone()];
+        let mut current = state;
+        while let Some(prev) = self.came_from.get(current) {
+            path.push(prev.clone());
+            current = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search where every step costs 1.
+///
+/// Stops as soon as a state matching `is_goal` is reached. Use `|_| false` to explore
+/// everything reachable from `start`.
+pub fn bfs<S, N, I, G>(start: S, mut neighbors: N, mut is_goal: G) -> SearchResult<S>
+where
+    S: Hash + Eq + Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = S>,
+    G: FnMut(&S) -> bool,
+{
+    let mut dist = HashMap::from([(start.clone(), 0)]);
+    let mut came_from = HashMap::new();
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(current) = queue.pop_front() {
+        if is_goal(&current) {
+            return SearchResult {
+                goal: Some(current),
+                dist,
+                came_from,
+            };
+        }
+
+        let next_dist = dist[&current] + 1;
+        for next in neighbors(&current) {
+            if dist.contains_key(&next) {
+                continue;
+            }
+            dist.insert(next.clone(), next_dist);
+            came_from.insert(next.clone(), current.clone());
+            queue.push_back(next);
+        }
+    }
+
+    SearchResult {
+        goal: None,
+        dist,
+        came_from,
+    }
+}
+
+/// Dijkstra's shortest path, `neighbors` yields `(state, cost)` pairs.
+pub fn dijkstra<S, N, I, G>(start: S, neighbors: N, is_goal: G) -> SearchResult<S>
+where
+    S: Hash + Eq + Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+    G: FnMut(&S) -> bool,
+{
+    astar(start, neighbors, is_goal, |_| 0)
+}
+
+#[derive(Debug)]
+struct Entry<S> {
+    priority: u64,
+    cost: u64,
+    state: S,
+}
+
+impl<S> PartialEq for Entry<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S> Eq for Entry<S> {}
+
+impl<S> Ord for Entry<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority)
+    }
+}
+
+impl<S> PartialOrd for Entry<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A* shortest path, `neighbors` yields `(state, cost)` pairs.
+///
+/// `heuristic` must never overestimate the remaining cost (and should be consistent),
+/// otherwise the returned path is not guaranteed to be optimal.
+pub fn astar<S, N, I, G, H>(
+    start: S,
+    mut neighbors: N,
+    mut is_goal: G,
+    mut heuristic: H,
+) -> SearchResult<S>
+where
+    S: Hash + Eq + Clone,
+    N: FnMut(&S) -> I,
+    I: IntoIterator<Item = (S, u64)>,
+    G: FnMut(&S) -> bool,
+    H: FnMut(&S) -> u64,
+{
+    let mut dist = HashMap::from([(start.clone(), 0)]);
+    let mut came_from = HashMap::new();
+    let mut open_set = BinaryHeap::from([Entry {
+        priority: heuristic(&start),
+        cost: 0,
+        state: start,
+    }]);
+
+    while let Some(Entry { cost, state, .. }) = open_set.pop() {
+        if cost > dist[&state] {
+            continue;
+        }
+
+        if is_goal(&state) {
+            return SearchResult {
+                goal: Some(state),
+                dist,
+                came_from,
+            };
+        }
+
+        for (next, step_cost) in neighbors(&state) {
+            let next_cost = cost + step_cost;
+            if next_cost >= *dist.get(&next).unwrap_or(&u64::MAX) {
+                continue;
+            }
+
+            dist.insert(next.clone(), next_cost);
+            came_from.insert(next.clone(), state.clone());
+            open_set.push(Entry {
+                priority: next_cost + heuristic(&next),
+                cost: next_cost,
+                state: next,
+            });
+        }
+    }
+
+    SearchResult {
+        goal: None,
+        dist,
+        came_from,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, dijkstra};
+
+    #[test]
+    fn bfs_over_bitmask_states() {
+        // flip one of 4 bits per step, from 0b0000 to 0b1011
+        let result = bfs(
+            0u8,
+            |&s| (0..4).map(move |b| s ^ (1 << b)),
+            |&s| s == 0b1011,
+        );
+        assert_eq!(result.cost(), Some(3));
+        assert_eq!(result.path().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn dijkstra_prefers_cheaper_detour() {
+        let edges = |&n: &char| match n {
+            'a' => vec![('b', 10), ('c', 1)],
+            'c' => vec![('d', 1)],
+            'd' => vec![('b', 1)],
+            _ => vec![],
+        };
+        let result = dijkstra('a', edges, |&n| n == 'b');
+        assert_eq!(result.cost(), Some(3));
+        assert_eq!(result.path(), Some(vec!['a', 'c', 'd', 'b']));
+    }
+
+    #[test]
+    fn astar_on_tuple_states() {
+        let goal = (5i32, 3i32);
+        let result = astar(
+            (0i32, 0i32),
+            |&(x, y)| [((x + 1, y), 1), ((x, y + 1), 1)],
+            |&s| s == goal,
+            |&(x, y)| ((goal.0 - x).abs() + (goal.1 - y).abs()) as u64,
+        );
+        assert_eq!(result.cost(), Some(8));
+    }
+
+    #[test]
+    fn exhausts_without_goal() {
+        let result = bfs(0u32, |&n| (n < 5).then_some(n + 1), |_| false);
+        assert_eq!(result.goal, None);
+        assert_eq!(result.dist.len(), 6);
+        assert_eq!(result.path_to(&5).unwrap(), vec![0, 1, 2, 3, 4, 5]);
+    }
+}
@@ -0,0 +1,240 @@
+use std::{
+    collections::{HashMap, hash_map},
+    fmt::Display,
+};
+
+use super::{Map2D, Pos2D};
+
+/// An unbounded grid that only stores occupied positions.
+///
+/// The bounding box of all occupied positions is kept up to date on every change.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Pos2D, T>,
+    bounds: Option<(Pos2D, Pos2D)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Inclusive `(min, max)` corners of the box enclosing every occupied position.
+    pub fn bounds(&self) -> Option<(Pos2D, Pos2D)> {
+        self.bounds
+    }
+
+    pub fn width(&self) -> usize {
+        self.bounds
+            .map_or(0, |(min, max)| (max.x - min.x + 1) as usize)
+    }
+
+    pub fn height(&self) -> usize {
+        self.bounds
+            .map_or(0, |(min, max)| (max.y - min.y + 1) as usize)
+    }
+
+    pub fn get(&self, position: &Pos2D) -> Option<&T> {
+        self.cells.get(position)
+    }
+
+    pub fn get_mut(&mut self, position: &Pos2D) -> Option<&mut T> {
+        self.cells.get_mut(position)
+    }
+
+    pub fn contains(&self, position: &Pos2D) -> bool {
+        self.cells.contains_key(position)
+    }
+
+    pub fn insert(&mut self, position: Pos2D, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => (position, position),
+            Some((min, max)) => (
+                Pos2D::new(min.x.min(position.x), min.y.min(position.y)),
+                Pos2D::new(max.x.max(position.x), max.y.max(position.y)),
+            ),
+        });
+        self.cells.insert(position, value)
+    }
+
+    pub fn remove(&mut self, position: &Pos2D) -> Option<T> {
+        let removed = self.cells.remove(position)?;
+
+        // only a position lying on the edge of the box can shrink it
+        if let Some((min, max)) = self.bounds
+            && (position.x == min.x
+                || position.x == max.x
+                || position.y == min.y
+                || position.y == max.y)
+        {
+            self.bounds = self.cells.keys().fold(None, |bounds, &p| {
+                Some(match bounds {
+                    None => (p, p),
+                    Some((min, max)) => (
+                        Pos2D::new(min.x.min(p.x), min.y.min(p.y)),
+                        Pos2D::new(max.x.max(p.x), max.y.max(p.y)),
+                    ),
+                })
+            });
+        }
+
+        Some(removed)
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, Pos2D, T> {
+        self.cells.iter()
+    }
+
+    pub fn positions(&self) -> hash_map::Keys<'_, Pos2D, T> {
+        self.cells.keys()
+    }
+
+    /// Occupied neighbors of `position`, which itself doesn't need to be occupied.
+    pub fn neighbors(
+        &self,
+        position: &Pos2D,
+        include_corners: bool,
+    ) -> impl Iterator<Item = (Pos2D, &T)> {
+        position
+            .neighbors_iter(include_corners)
+            .filter_map(|pos| Some((pos, self.cells.get(&pos)?)))
+    }
+
+    pub fn count_neighbors(&self, position: &Pos2D, include_corners: bool) -> usize {
+        self.neighbors(position, include_corners).count()
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    T: Clone,
+{
+    /// Collects every tile of `map` for which `keep` returns `true`.
+    pub fn from_map(map: &Map2D<T>, keep: impl Fn(&T) -> bool) -> Self {
+        map.tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, tile)| keep(tile))
+            .map(|(idx, tile)| (map.idx2pos(idx), tile.clone()))
+            .collect()
+    }
+
+    /// Copies the grid's bounding box into a dense map, filling holes with `empty`.
+    ///
+    /// The map's `(0, 0)` is the top left corner of [`SparseGrid::bounds`].
+    pub fn to_map(&self, empty: T) -> Map2D<T> {
+        let (width, height) = (self.width(), self.height());
+        let mut tiles = vec![empty; width * height];
+        if let Some((min, _)) = self.bounds {
+            for (pos, value) in &self.cells {
+                let idx = (pos.y - min.y) as usize * width + (pos.x - min.x) as usize;
+                tiles[idx] = value.clone();
+            }
+        }
+
+        Map2D {
+            width,
+            height,
+            tiles,
+            markers: HashMap::new(),
+            wrapping: false,
+        }
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    T: Display,
+{
+    /// Renders the bounding box as text, one line per row, drawing holes with `empty`.
+    pub fn render(&self, empty: char) -> String {
+        let mut out = String::new();
+        let Some((min, max)) = self.bounds else {
+            return out;
+        };
+
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                match self.cells.get(&Pos2D::new(x, y)) {
+                    Some(value) => out.push_str(&value.to_string()),
+                    None => out.push(empty),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn display(&self, empty: char) {
+        println!("\n{}", self.render(empty));
+    }
+}
+
+impl<T> FromIterator<(Pos2D, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Pos2D, T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        for (position, value) in iter {
+            grid.insert(position, value);
+        }
+        grid
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SparseGrid<T> {
+    type Item = (&'a Pos2D, &'a T);
+    type IntoIter = hash_map::Iter<'a, Pos2D, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SparseGrid;
+    use crate::utils::{Map2D, Pos2D};
+
+    #[test]
+    fn tracks_bounds() {
+        let mut grid = SparseGrid::new();
+        grid.insert(Pos2D::new(-2, 3), '#');
+        grid.insert(Pos2D::new(4, -1), '#');
+        grid.insert(Pos2D::new(0, 0), '#');
+        assert_eq!(grid.bounds(), Some((Pos2D::new(-2, -1), Pos2D::new(4, 3))));
+        assert_eq!((grid.width(), grid.height()), (7, 5));
+
+        grid.remove(&Pos2D::new(4, -1));
+        assert_eq!(grid.bounds(), Some((Pos2D::new(-2, 0), Pos2D::new(0, 3))));
+        grid.remove(&Pos2D::new(-2, 3));
+        grid.remove(&Pos2D::new(0, 0));
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn renders_and_counts_neighbors() {
+        let map = Map2D::<char>::parse("#..\n.#.\n..#\n", &[]).unwrap();
+        let grid = SparseGrid::from_map(&map, |&c| c == '#');
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.count_neighbors(&Pos2D::new(1, 1), true), 2);
+        assert_eq!(grid.count_neighbors(&Pos2D::new(1, 1), false), 0);
+        assert_eq!(grid.render('.'), "#..\n.#.\n..#\n");
+        assert_eq!(grid.to_map('.').tiles, map.tiles);
+    }
+}
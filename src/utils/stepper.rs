@@ -0,0 +1,271 @@
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, Read, Write},
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use super::{Map2D, Overlay, Pos2D};
+use crate::template::{ANSI_BOLD, ANSI_RESET, ANSI_YELLOW};
+
+type StepFn<T> = Box<dyn FnMut(&Map2D<T>) -> Option<Map2D<T>>>;
+
+/// Steps through the generations of a grid simulation in the terminal.
+///
+/// Generations come from recorded snapshots ([`Stepper::from_frames`]) or are computed on
+/// demand by a step closure ([`Stepper::from_fn`]), and are kept so that stepping back is free.
+///
+/// Keys in [`Stepper::run`]: `→`/`l`/`n` next, `←`/`h`/`p` previous, `space` play/pause,
+/// a number followed by `g` jumps to that generation, `q` quits.
+pub struct Stepper<T> {
+    frames: Vec<Map2D<T>>,
+    step: Option<StepFn<T>>,
+    generation: usize,
+    delay: Duration,
+    viewport: Option<(Pos2D, usize, usize)>,
+}
+
+impl<T: Display + PartialEq> Stepper<T> {
+    pub fn from_frames(frames: Vec<Map2D<T>>) -> Self {
+        assert!(!frames.is_empty(), "a stepper needs at least one frame");
+        Stepper {
+            frames,
+            step: None,
+            generation: 0,
+            delay: Duration::from_millis(100),
+            viewport: None,
+        }
+    }
+
+    /// Starts from `initial`, computing the next generation with `step` until it returns `None`.
+    pub fn from_fn(
+        initial: Map2D<T>,
+        step: impl FnMut(&Map2D<T>) -> Option<Map2D<T>> + 'static,
+    ) -> Self {
+        Stepper {
+            step: Some(Box::new(step)),
+            ..Stepper::from_frames(vec![initial])
+        }
+    }
+
+    /// Time between two generations while playing.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Only shows part of the map, see [`super::Renderer::viewport`].
+    pub fn viewport(mut self, origin: Pos2D, width: usize, height: usize) -> Self {
+        self.viewport = Some((origin, width, height));
+        self
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn current(&self) -> &Map2D<T> {
+        &self.frames[self.generation]
+    }
+
+    /// Moves to the next generation, computing it if needed. Returns `false` at the end.
+    pub fn forward(&mut self) -> bool {
+        if self.generation + 1 == self.frames.len() {
+            let Some(step) = self.step.as_mut() else {
+                return false;
+            };
+            match step(&self.frames[self.generation]) {
+                Some(next) => self.frames.push(next),
+                None => {
+                    // the simulation is over, no need to ask again
+                    self.step = None;
+                    return false;
+                }
+            }
+        }
+        self.generation += 1;
+        true
+    }
+
+    /// Moves to the previous generation. Returns `false` at the first one.
+    pub fn back(&mut self) -> bool {
+        if self.generation == 0 {
+            return false;
+        }
+        self.generation -= 1;
+        true
+    }
+
+    /// Moves to `generation`, or to the last one if the simulation ends before it.
+    pub fn jump(&mut self, generation: usize) {
+        self.generation = self.generation.min(generation);
+        while self.generation < generation && self.forward() {}
+    }
+
+    /// Tiles that differ from the previous generation.
+    pub fn changed(&self) -> Vec<usize> {
+        let Some(previous) = self.generation.checked_sub(1).map(|g| &self.frames[g]) else {
+            return vec![];
+        };
+        let current = self.current();
+        (0..current.tiles.len())
+            .filter(|&idx| previous.tiles.get(idx) != Some(&current.tiles[idx]))
+            .collect()
+    }
+
+    /// The current generation with its header, changed tiles highlighted.
+    pub fn render(&self, colored: bool) -> String {
+        let changed = self.changed();
+        let known = match self.step {
+            Some(_) => format!("{}+", self.frames.len()),
+            None => self.frames.len().to_string(),
+        };
+        let mut renderer = self
+            .current()
+            .renderer()
+            .colored(colored)
+            .overlay(Overlay::new("changed", changed.iter().copied()).color(ANSI_YELLOW));
+        if let Some((origin, width, height)) = self.viewport {
+            renderer = renderer.viewport(origin, width, height);
+        }
+
+        let (bold, reset) = if colored {
+            (ANSI_BOLD, ANSI_RESET)
+        } else {
+            ("", "")
+        };
+        format!(
+            "{bold}Generation {}{reset} of {known} ({} changed)\n\n{}",
+            self.generation,
+            changed.len(),
+            renderer.render()
+        )
+    }
+
+    /// Runs the interactive stepper until `q` is pressed. Needs a Unix terminal with `stty`.
+    pub fn run(&mut self) -> io::Result<()> {
+        let mut tty = File::open("/dev/tty")?;
+        let _raw = RawMode::enable()?;
+        let mut stdout = io::stdout();
+
+        let mut playing = false;
+        let mut number = String::new();
+        let mut last_step = Instant::now();
+        let mut redraw = true;
+        let mut buf = [0u8; 16];
+
+        loop {
+            if redraw {
+                let status = if playing { "playing" } else { "paused" };
+                write!(stdout, "\x1b[H\x1b[2J{}", self.render(true))?;
+                write!(
+                    stdout,
+                    "\n[{status}] ←/→ step, space play/pause, <n>g jump, q quit > {number}"
+                )?;
+                stdout.flush()?;
+                redraw = false;
+            }
+
+            // returns 0 bytes after a short timeout, see `RawMode`
+            let read = tty.read(&mut buf)?;
+            if read == 0 {
+                if playing && last_step.elapsed() >= self.delay {
+                    playing = self.forward();
+                    last_step = Instant::now();
+                    redraw = true;
+                }
+                continue;
+            }
+
+            redraw = true;
+            match &buf[..read] {
+                b"q" => break,
+                b" " => {
+                    playing = !playing;
+                    last_step = Instant::now();
+                }
+                b"\x1b[C" | b"l" | b"n" => {
+                    self.forward();
+                }
+                b"\x1b[D" | b"h" | b"p" => {
+                    self.back();
+                }
+                b"g" => {
+                    if let Ok(generation) = number.parse() {
+                        self.jump(generation);
+                    }
+                    number.clear();
+                }
+                [digit @ b'0'..=b'9'] => number.push(*digit as char),
+                _ => number.clear(),
+            }
+        }
+
+        writeln!(stdout)?;
+        Ok(())
+    }
+}
+
+/// Puts the terminal in non-canonical mode without echo, where reads return after 0.1s even
+/// without input, and restores the previous settings when dropped.
+struct RawMode(String);
+
+impl RawMode {
+    fn enable() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "min", "0", "time", "1"])?;
+        Ok(RawMode(saved.trim().to_string()))
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.0]);
+    }
+}
+
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(File::open("/dev/tty")?)
+        .output()?;
+    if !output.status.success() {
+        let msg = String::from_utf8_lossy(&output.stderr).to_string();
+        return Err(io::Error::other(msg));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Stepper;
+    use crate::utils::Map2D;
+
+    #[test]
+    fn steps_and_diffs() {
+        let map = Map2D::<char>::parse("....\n", &[]).unwrap();
+        // fills one more tile every generation
+        let mut stepper = Stepper::from_fn(map, |map| {
+            let idx = map.tiles.iter().position(|&c| c == '.')?;
+            let mut next = map.clone();
+            next.tiles[idx] = '#';
+            Some(next)
+        });
+
+        assert!(!stepper.back());
+        stepper.jump(10);
+        assert_eq!(stepper.generation(), 4);
+        assert!(!stepper.forward());
+        assert_eq!(stepper.changed(), vec![3]);
+
+        stepper.jump(1);
+        assert_eq!(stepper.current().render(), "#...\n");
+        assert!(stepper.back());
+        assert!(stepper.changed().is_empty());
+        assert_eq!(
+            stepper.render(false),
+            "Generation 0 of 5 (0 changed)\n\n....\n"
+        );
+    }
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use super::{Map2D, Pos2D};
+
+impl<T> Map2D<T>
+where
+    T: Clone,
+{
+    /// Builds a `width` x `height` map where the tile at `(x, y)` of `self` lands at `f(x, y)`.
+    fn remap(
+        &self,
+        width: usize,
+        height: usize,
+        f: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Self {
+        let move_idx = |idx: usize| {
+            let (x, y) = f(idx % self.width, idx / self.width);
+            y * width + x
+        };
+
+        let mut tiles = self.tiles.clone();
+        for (idx, tile) in self.tiles.iter().enumerate() {
+            tiles[move_idx(idx)] = tile.clone();
+        }
+
+        let markers = self
+            .markers
+            .iter()
+            .map(|(&c, idxs)| (c, idxs.iter().map(|&idx| move_idx(idx)).collect()))
+            .collect();
+
+        Map2D {
+            width,
+            height,
+            tiles,
+            markers,
+            wrapping: self.wrapping,
+        }
+    }
+
+    /// Mirrors the map along its main diagonal: `(x, y)` becomes `(y, x)`.
+    pub fn transpose(&self) -> Self {
+        self.remap(self.height, self.width, |x, y| (y, x))
+    }
+
+    /// Rotates the map by 90° clockwise.
+    pub fn rotate_cw(&self) -> Self {
+        let h = self.height;
+        self.remap(self.height, self.width, |x, y| (h - 1 - y, x))
+    }
+
+    /// Rotates the map by 90° counter-clockwise.
+    pub fn rotate_ccw(&self) -> Self {
+        let w = self.width;
+        self.remap(self.height, self.width, |x, y| (y, w - 1 - x))
+    }
+
+    /// Rotates the map by 180°.
+    pub fn rotate_180(&self) -> Self {
+        let (w, h) = (self.width, self.height);
+        self.remap(w, h, |x, y| (w - 1 - x, h - 1 - y))
+    }
+
+    /// Mirrors the map left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        let w = self.width;
+        self.remap(self.width, self.height, |x, y| (w - 1 - x, y))
+    }
+
+    /// Mirrors the map top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        let h = self.height;
+        self.remap(self.width, self.height, |x, y| (x, h - 1 - y))
+    }
+
+    /// All 8 rotations and reflections of the map, starting with an unchanged copy.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut orientations = Vec::with_capacity(8);
+        let mut current = self.remap(self.width, self.height, |x, y| (x, y));
+        for _ in 0..4 {
+            let next = current.rotate_cw();
+            let flipped = current.flip_horizontal();
+            orientations.push(current);
+            orientations.push(flipped);
+            current = next;
+        }
+        orientations
+    }
+}
+
+impl<T> Map2D<T> {
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.tiles[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.tiles.chunks(self.width)
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.tiles.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// Borrows the `width` x `height` rectangle whose top left corner is `origin`.
+    ///
+    /// Returns `None` if the rectangle doesn't fit in the map.
+    pub fn window(&self, origin: Pos2D, width: usize, height: usize) -> Option<Window<'_, T>> {
+        let (x, y) = (
+            usize::try_from(origin.x).ok()?,
+            usize::try_from(origin.y).ok()?,
+        );
+        if x + width > self.width || y + height > self.height {
+            return None;
+        }
+
+        Some(Window {
+            map: self,
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+}
+
+/// A borrowed rectangular part of a [`Map2D`], see [`Map2D::window`].
+#[derive(Debug)]
+pub struct Window<'a, T> {
+    map: &'a Map2D<T>,
+    x: usize,
+    y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl<T> Clone for Window<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Window<'_, T> {}
+
+impl<'a, T> Window<'a, T> {
+    /// Position of the window's top left corner in the underlying map.
+    pub fn origin(&self) -> Pos2D {
+        Pos2D::new(self.x as i32, self.y as i32)
+    }
+
+    /// Tile at `(x, y)` relative to the window's origin.
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a T> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(&self.map.tiles[(self.y + y) * self.map.width + self.x + x])
+    }
+
+    pub fn row(&self, y: usize) -> &'a [T] {
+        let start = (self.y + y) * self.map.width + self.x;
+        &self.map.tiles[start..start + self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> {
+        let window = *self;
+        (0..self.height).map(move |y| window.row(y))
+    }
+
+    pub fn tiles(&self) -> impl Iterator<Item = &'a T> {
+        self.rows().flatten()
+    }
+}
+
+impl<T> Window<'_, T>
+where
+    T: Clone,
+{
+    /// Copies the window into a standalone map, keeping the markers that lie inside it.
+    pub fn to_map(&self) -> Map2D<T> {
+        let mut markers: HashMap<char, Vec<usize>> = HashMap::new();
+        for (&c, idxs) in &self.map.markers {
+            for &idx in idxs {
+                let (x, y) = (idx % self.map.width, idx / self.map.width);
+                if (self.x..self.x + self.width).contains(&x)
+                    && (self.y..self.y + self.height).contains(&y)
+                {
+                    let local = (y - self.y) * self.width + x - self.x;
+                    markers.entry(c).or_default().push(local);
+                }
+            }
+        }
+
+        Map2D {
+            width: self.width,
+            height: self.height,
+            tiles: self.tiles().cloned().collect(),
+            markers,
+            wrapping: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::{Map2D, Pos2D};
+
+    fn to_string(map: &Map2D<char>) -> String {
+        map.rows()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn transforms_keep_markers() {
+        let map = Map2D::<char>::parse("abS\ndef\n", &['S']).unwrap();
+
+        let rotated = map.rotate_cw();
+        assert_eq!(to_string(&rotated), "da\neb\nfS");
+        assert_eq!(rotated.marker('S'), Some(5));
+
+        assert_eq!(to_string(&map.rotate_ccw()), "Sf\nbe\nad");
+        assert_eq!(to_string(&map.transpose()), "ad\nbe\nSf");
+        assert_eq!(to_string(&map.flip_horizontal()), "Sba\nfed");
+        assert_eq!(to_string(&map.flip_vertical()), "def\nabS");
+        assert_eq!(to_string(&map.rotate_180()), "fed\nSba");
+        assert_eq!(map.rotate_180().marker('S'), Some(3));
+    }
+
+    #[test]
+    fn lists_all_orientations() {
+        let map = Map2D::<char>::parse("ab\ncd\n", &[]).unwrap();
+        let mut all: Vec<_> = map.orientations().iter().map(to_string).collect();
+        assert_eq!(all[0], "ab\ncd");
+        all.sort();
+        all.dedup();
+        assert_eq!(all.len(), 8);
+    }
+
+    #[test]
+    fn borrows_windows() {
+        let map = Map2D::<char>::parse("abcd\nefgh\nijkl\n", &['g']).unwrap();
+        assert_eq!(map.column(1).collect::<String>(), "bfj");
+
+        let window = map.window(Pos2D::new(1, 1), 2, 2).unwrap();
+        assert_eq!(window.tiles().collect::<String>(), "fgjk");
+        assert_eq!(window.get(1, 0), Some(&'g'));
+        assert_eq!(window.to_map().marker('g'), Some(1));
+        assert!(map.window(Pos2D::new(3, 0), 2, 1).is_none());
+    }
+}